@@ -1,39 +1,96 @@
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
 use axum::{
     body::Body,
     debug_handler,
-    extract::State,
-    response::{IntoResponse, Response},
-    routing::get,
+    extract::{Request, State},
+    response::IntoResponse,
+    routing::{any, get},
     Router,
 };
-use base64::{
-    alphabet::URL_SAFE,
-    engine::{general_purpose::NO_PAD, GeneralPurpose},
-    Engine,
-};
-use hyper::{
-    header::{CONTENT_TYPE, ETAG, IF_NONE_MATCH},
-    HeaderMap, StatusCode,
-};
-use static_assets::Map;
+use hyper::StatusCode;
+use static_assets::AssetSource;
+pub use static_assets_http::{CachePolicy, Config, Fallback};
+pub use static_assets_hyper::{StaticFallthrough, StaticLayer};
 pub use static_assets_macros::assets;
 use tracing::{debug, error};
 
-const ETAG_STRING_SIZE: usize = 45;
-
 #[derive(Debug, thiserror::Error)]
 enum Error {
     #[error("http")]
     Http(#[from] axum::http::Error),
 }
 
-pub fn assets_router(assets: &'static Map<'static>) -> Router {
+// We look the asset up on each request, rather than keeping a copy, so
+// that sources which change at runtime serve the current content.
+#[derive(Clone)]
+struct AssetState {
+    assets: Arc<dyn AssetSource + Send + Sync>,
+    name: Arc<str>,
+    config: Arc<Config>,
+}
+
+#[derive(Clone)]
+struct PathState {
+    assets: Arc<dyn AssetSource + Send + Sync>,
+    config: Arc<Config>,
+}
+
+/// Routes each name in an [`AssetSource`], such as a `&'static Map`, or an
+/// `Arc` of a source loaded at runtime.
+///
+/// Directories with an index file (see [`Config::index_files`]) are also
+/// routed both with and without a trailing `/`, the latter redirecting to
+/// the former.
+///
+/// With [`Config::fallback`] set, the router's fallback serves the page for
+/// unknown paths, so it can't be merged with another router that has one.
+pub fn assets_router(assets: impl AssetSource + Send + Sync + 'static) -> Router {
+    assets_router_with_config(assets, Config::default())
+}
+
+pub fn assets_router_with_config(
+    assets: impl AssetSource + Send + Sync + 'static,
+    config: Config,
+) -> Router {
+    let assets: Arc<dyn AssetSource + Send + Sync> = Arc::new(assets);
+    let config = Arc::new(config);
     let mut rt = Router::new();
+    let mut routed = BTreeSet::new();
+    let mut dirs = BTreeSet::new();
 
-    for asset in assets.iter() {
-        let path = format!("/{}", asset.name);
+    for name in assets.names() {
+        let path = format!("/{}", name);
         debug!(?path, "adding asset");
-        rt = rt.route(&path, get(get_asset).with_state(asset));
+        if let Some(dir) = static_assets_http::index_dir(&name, &config) {
+            dirs.insert(format!("/{}", dir));
+        }
+        let state = AssetState {
+            assets: assets.clone(),
+            name: name.into(),
+            config: config.clone(),
+        };
+        rt = rt.route(&path, get(get_asset).with_state(state));
+        routed.insert(path);
+    }
+
+    // Each of these is resolved per request, as with `StaticService`.
+    let state = PathState {
+        assets: assets.clone(),
+        config: config.clone(),
+    };
+    for dir in dirs {
+        let bare = dir.trim_end_matches('/');
+        for path in [dir.as_str(), bare] {
+            if !path.is_empty() && routed.insert(path.to_owned()) {
+                debug!(?path, "adding directory");
+                rt = rt.route(path, get(get_path).with_state(state.clone()));
+            }
+        }
+    }
+    if config.fallback.is_some() {
+        rt = rt.fallback_service(any(get_path).with_state(state));
     }
 
     rt
@@ -41,31 +98,25 @@ pub fn assets_router(assets: &'static Map<'static>) -> Router {
 
 #[debug_handler]
 async fn get_asset(
-    request_headers: HeaderMap,
-    asset: State<static_assets::Asset<'static>>,
+    State(state): State<AssetState>,
+    request: Request,
 ) -> Result<impl IntoResponse, Error> {
-    let mut buf: [u8; 45] = [0u8; ETAG_STRING_SIZE];
-    let etag = encode_etag(&mut buf, &asset);
+    let Some(asset) = state.assets.get(&state.name) else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+    let resp = static_assets_http::serve_ref(&request, &asset, &state.config)?;
 
-    let not_modified = request_headers
-        .get(IF_NONE_MATCH)
-        .and_then(|val| val.to_str().ok())
-        .map(|val| val == etag)
-        .unwrap_or(false);
-
-    if not_modified {
-        let resp = Response::builder()
-            .status(StatusCode::NOT_MODIFIED)
-            .body(Body::default())?;
-        return Ok(resp);
-    }
+    Ok(resp.map(axum_body).into_response())
+}
 
-    let resp = Response::builder()
-        .header(CONTENT_TYPE, asset.content_type)
-        .header(ETAG, etag)
-        .body(Body::from(asset.content))?;
+#[debug_handler]
+async fn get_path(
+    State(state): State<PathState>,
+    request: Request,
+) -> Result<impl IntoResponse, Error> {
+    let resp = static_assets_http::serve(&request, &*state.assets, &state.config)?;
 
-    Ok(resp)
+    Ok(resp.map(axum_body).into_response())
 }
 
 impl IntoResponse for Error {
@@ -75,15 +126,11 @@ impl IntoResponse for Error {
     }
 }
 
-fn encode_etag<'a>(buf: &'a mut [u8; ETAG_STRING_SIZE], asset: &static_assets::Asset) -> &'a str {
-    const BASE64_ENGINE: GeneralPurpose = GeneralPurpose::new(&URL_SAFE, NO_PAD);
-    let mut off = 0;
-    buf[off] = b'"';
-    off += 1;
-    off += BASE64_ENGINE
-        .encode_slice(asset.digest, &mut buf[off..])
-        .unwrap();
-    buf[off] = b'"';
-    off += 1;
-    std::str::from_utf8(&buf[..off]).expect("Should only generate ASCII")
+fn axum_body(body: static_assets_http::Body<'static>) -> Body {
+    match body {
+        static_assets_http::Body::Empty => Body::empty(),
+        static_assets_http::Body::Borrowed(content) => Body::from(content),
+        static_assets_http::Body::Owned(content) => Body::from(content),
+        static_assets_http::Body::Shared(content) => Body::from(content),
+    }
 }
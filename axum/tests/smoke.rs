@@ -1,16 +1,25 @@
 use anyhow::{Context, Result};
-use axum::{body::Body, routing::get_service, Router};
+use axum::{
+    body::Body,
+    routing::{get, get_service, post},
+    Router,
+};
 use headers::{ContentType, HeaderMapExt};
 use http_body_util::BodyExt;
 use hyper::{
-    header::{ETAG, IF_NONE_MATCH},
+    header::{
+        ACCEPT, ACCEPT_ENCODING, CACHE_CONTROL, CONTENT_ENCODING, CONTENT_RANGE, ETAG,
+        IF_NONE_MATCH, IF_RANGE, LOCATION, RANGE, VARY,
+    },
     Request, StatusCode,
 };
-use static_assets::Map;
+use static_assets::{Layered, Map, OwnedMap};
 use tower::ServiceExt;
 use tracing::warn;
 
-use static_assets_axum::{assets, assets_router};
+use static_assets_axum::{
+    assets, assets_router, assets_router_with_config, CachePolicy, Config, Fallback, StaticLayer,
+};
 
 static ASSETS: Map = assets!("../macros/tests/assets");
 
@@ -161,3 +170,337 @@ async fn should_serve_nested_in_axum() -> Result<()> {
     assert_eq!(bodystr, "<p>Hi!</p>\n");
     Ok(())
 }
+
+#[tokio::test]
+async fn should_serve_negotiated_encoding() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let srv = assets_router(&ASSETS);
+    let req = Request::builder()
+        .uri("/css/canary.css")
+        .header(ACCEPT_ENCODING, "br")
+        .body(Body::empty())?;
+    let resp = srv.oneshot(req).await.context("Fetch response")?;
+    let (parts, body) = resp.into_parts();
+
+    assert_eq!(parts.status, StatusCode::OK);
+    assert_eq!(
+        parts
+            .headers
+            .get(CONTENT_ENCODING)
+            .expect("Content-Encoding"),
+        "br"
+    );
+    assert_eq!(parts.headers.get(VARY).expect("Vary"), "accept-encoding");
+
+    let body = body.collect().await?.to_bytes();
+    let asset = ASSETS.get("css/canary.css").expect("asset");
+    assert_eq!(&body[..], asset.brotli.expect("brotli variant"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_identity_without_accept_encoding() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let srv = assets_router(&ASSETS);
+    let req = Request::builder()
+        .uri("/css/canary.css")
+        .body(Body::empty())?;
+    let resp = srv.oneshot(req).await.context("Fetch response")?;
+    let (parts, body) = resp.into_parts();
+
+    assert_eq!(parts.status, StatusCode::OK);
+    assert!(parts.headers.get(CONTENT_ENCODING).is_none());
+
+    let body = body.collect().await?.to_bytes();
+    let asset = ASSETS.get("css/canary.css").expect("asset");
+    assert_eq!(&body[..], asset.content);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_range_matching_if_range() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let srv = assets_router(&ASSETS);
+    let req = Request::builder().uri("/canary.html").body(Body::empty())?;
+    let resp = srv.clone().oneshot(req).await.context("Fetch response")?;
+    let (parts, _) = resp.into_parts();
+    let entity_tag = parts.headers.get(ETAG).expect("some ETag header");
+
+    let req = Request::builder()
+        .uri("/canary.html")
+        .header(RANGE, "bytes=-4")
+        .header(IF_RANGE, entity_tag)
+        .body(Body::empty())?;
+    let resp = srv.oneshot(req).await.context("Fetch response")?;
+    let (parts, body) = resp.into_parts();
+
+    assert_eq!(parts.status, StatusCode::PARTIAL_CONTENT);
+    assert_eq!(
+        parts.headers.get(CONTENT_RANGE).expect("Content-Range"),
+        "bytes 7-10/11"
+    );
+    let body = body.collect().await?.to_bytes();
+    assert_eq!(&body[..], b"/p>\n");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_reject_unsatisfiable_range() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let srv = assets_router(&ASSETS);
+    let req = Request::builder()
+        .uri("/canary.html")
+        .header(RANGE, "bytes=11-")
+        .body(Body::empty())?;
+    let resp = srv.oneshot(req).await.context("Fetch response")?;
+    let (parts, _) = resp.into_parts();
+
+    assert_eq!(parts.status, StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(
+        parts.headers.get(CONTENT_RANGE).expect("Content-Range"),
+        "bytes */11"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_not_modified_for_wildcard() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let srv = assets_router(&ASSETS);
+    let req = Request::builder()
+        .uri("/canary.html")
+        .header(IF_NONE_MATCH, "*")
+        .body(Body::empty())?;
+    let resp = srv.oneshot(req).await.context("Fetch response")?;
+    let (parts, _) = resp.into_parts();
+
+    assert_eq!(parts.status, StatusCode::NOT_MODIFIED);
+    assert!(parts.headers.get(ETAG).is_some(), "ETag on 304");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_configured_cache_control() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let config = Config {
+        cache_policy: CachePolicy::default().with_content_type("text/*", "public, max-age=60")?,
+
+        ..Config::default()
+    };
+    let srv = assets_router_with_config(&ASSETS, config);
+
+    let req = Request::builder().uri("/canary.html").body(Body::empty())?;
+    let resp = srv.oneshot(req).await.context("Fetch response")?;
+    assert_eq!(
+        resp.headers().get(CACHE_CONTROL).expect("Cache-Control"),
+        "public, max-age=60"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_owned_map() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let assets = OwnedMap::builder()
+        .file("hello.txt", "Hello, world")
+        .build()?;
+    let srv = assets_router(assets);
+
+    let req = Request::builder().uri("/hello.txt").body(Body::empty())?;
+    let resp = srv.oneshot(req).await.context("Fetch response")?;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers().typed_get::<ContentType>(),
+        Some(ContentType::text())
+    );
+    let body = resp.into_body().collect().await?.to_bytes();
+    assert_eq!(body, "Hello, world");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_layered_overrides() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let overrides = OwnedMap::builder()
+        .file("canary.html", "<p>Overridden</p>")
+        .build()?;
+    let srv = assets_router(
+        Layered::new()
+            .layer("overrides", overrides)
+            .layer("embedded", &ASSETS),
+    );
+
+    let req = Request::builder().uri("/canary.html").body(Body::empty())?;
+    let resp = srv.clone().oneshot(req).await.context("Fetch response")?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = resp.into_body().collect().await?.to_bytes();
+    assert_eq!(body, "<p>Overridden</p>");
+
+    let req = Request::builder()
+        .uri("/css/canary.css")
+        .body(Body::empty())?;
+    let resp = srv.oneshot(req).await.context("Fetch response")?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_route_directory_index() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let assets = OwnedMap::builder()
+        .file("index.html", "<p>Home</p>")
+        .file("docs/index.html", "<p>Docs</p>")
+        .build()?;
+    let srv = assets_router(assets);
+
+    for (path, expected) in [("/", "<p>Home</p>"), ("/docs/", "<p>Docs</p>")] {
+        let req = Request::builder().uri(path).body(Body::empty())?;
+        let resp = srv.clone().oneshot(req).await.context("Fetch response")?;
+        assert_eq!(resp.status(), StatusCode::OK, "{}", path);
+        let body = resp.into_body().collect().await?.to_bytes();
+        assert_eq!(body, expected);
+    }
+
+    let req = Request::builder().uri("/docs").body(Body::empty())?;
+    let resp = srv.oneshot(req).await.context("Fetch response")?;
+    assert_eq!(resp.status(), StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(resp.headers()[LOCATION], "docs/");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_redirect_within_nested_router() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let assets = OwnedMap::builder()
+        .file("docs/index.html", "<p>Docs</p>")
+        .build()?;
+    let app = Router::new().nest_service("/static", assets_router(assets));
+
+    let req = Request::builder().uri("/static/docs").body(Body::empty())?;
+    let resp = app.clone().oneshot(req).await.context("Fetch response")?;
+    assert_eq!(resp.status(), StatusCode::PERMANENT_REDIRECT);
+    let location = resp.headers()[LOCATION].to_str()?;
+    assert_eq!(location, "docs/");
+
+    // Resolved against the request URI, as a client would.
+    let uri = format!("/static/{}", location);
+    let req = Request::builder().uri(uri).body(Body::empty())?;
+    let resp = app.oneshot(req).await.context("Fetch response")?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = resp.into_body().collect().await?.to_bytes();
+    assert_eq!(body, "<p>Docs</p>");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_route_spa_fallback() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let assets = OwnedMap::builder()
+        .file("index.html", "<div id=\"app\"></div>")
+        .build()?;
+    let config = Config {
+        fallback: Some(Fallback::default().with_excluded_prefix("/api")),
+        ..Config::default()
+    };
+    let srv = assets_router_with_config(assets, config);
+
+    let req = Request::builder()
+        .uri("/users/42")
+        .header(ACCEPT, "text/html")
+        .body(Body::empty())?;
+    let resp = srv.clone().oneshot(req).await.context("Fetch response")?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()[CACHE_CONTROL], "no-cache");
+    let body = resp.into_body().collect().await?.to_bytes();
+    assert_eq!(body, "<div id=\"app\"></div>");
+
+    let req = Request::builder()
+        .uri("/api/users")
+        .header(ACCEPT, "text/html")
+        .body(Body::empty())?;
+    let resp = srv.oneshot(req).await.context("Fetch response")?;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_assets_in_front_of_routes() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let srv = Router::new()
+        .route("/canary.html", post(|| async { "posted" }))
+        .route("/api/users", get(|| async { "users" }))
+        .layer(StaticLayer::new(&ASSETS));
+
+    let req = Request::builder().uri("/canary.html").body(Body::empty())?;
+    let resp = srv.clone().oneshot(req).await.context("Fetch response")?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = resp.into_body().collect().await?.to_bytes();
+    assert_eq!(body, "<p>Hi!</p>\n");
+
+    let req = Request::builder()
+        .method("POST")
+        .uri("/canary.html")
+        .body(Body::empty())?;
+    let resp = srv.clone().oneshot(req).await.context("Fetch response")?;
+    let body = resp.into_body().collect().await?.to_bytes();
+    assert_eq!(body, "posted");
+
+    let req = Request::builder().uri("/api/users").body(Body::empty())?;
+    let resp = srv.oneshot(req).await.context("Fetch response")?;
+    let body = resp.into_body().collect().await?.to_bytes();
+    assert_eq!(body, "users");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_fall_back_only_for_unrouted_paths() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let srv = Router::new()
+        .route("/login", get(|| async { "login form" }))
+        .layer(StaticLayer::new(&ASSETS).with_fallback(Fallback::new("canary.html")));
+
+    let req = Request::builder()
+        .uri("/login")
+        .header(ACCEPT, "text/html")
+        .body(Body::empty())?;
+    let resp = srv.clone().oneshot(req).await.context("Fetch response")?;
+    let body = resp.into_body().collect().await?.to_bytes();
+    assert_eq!(body, "login form");
+
+    let req = Request::builder()
+        .uri("/settings")
+        .header(ACCEPT, "text/html")
+        .body(Body::empty())?;
+    let resp = srv.oneshot(req).await.context("Fetch response")?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = resp.into_body().collect().await?.to_bytes();
+    assert_eq!(body, "<p>Hi!</p>\n");
+
+    Ok(())
+}
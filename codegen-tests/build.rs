@@ -1,12 +1,34 @@
 use std::{env, path::PathBuf};
 
 use anyhow::Result;
+use static_assets_codegen::{Fingerprint, Generator, Integrity};
 
 pub fn main() -> Result<()> {
     let target_dir = PathBuf::from(env::var("OUT_DIR").expect("$OUT_DIR"));
-    let target = target_dir.join("canary-gen.rs");
 
-    static_assets_codegen::generate_to_file("../macros/tests/assets".as_ref(), target)?;
+    println!("cargo:rerun-if-changed=build.rs");
+
+    Generator::new("../macros/tests/assets")
+        .target(target_dir.join("canary-gen.rs"))
+        .write_for_build_script()?;
+
+    Generator::new("../macros/tests/assets")
+        .fingerprint(Fingerprint::Alongside)
+        .integrity([Integrity::Sha384, Integrity::Sha512])
+        .url_prefix("/static")
+        .manifest_target(target_dir.join("manifest.json"))
+        .write()?;
+
+    for (fingerprint, file) in [
+        (Fingerprint::Alongside, "fingerprint-alongside-gen.rs"),
+        (Fingerprint::Instead, "fingerprint-instead-gen.rs"),
+    ] {
+        Generator::new("../macros/tests/assets")
+            .fingerprint(fingerprint)
+            .integrity([Integrity::Sha384, Integrity::Sha512])
+            .target(target_dir.join(file))
+            .write_for_build_script()?;
+    }
 
     Ok(())
 }
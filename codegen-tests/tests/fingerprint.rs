@@ -0,0 +1,70 @@
+use std::collections::BTreeSet;
+
+mod assets {
+    use static_assets::Map;
+
+    pub(crate) static ALONGSIDE: Map =
+        include!(concat!(env!("OUT_DIR"), "/fingerprint-alongside-gen.rs"));
+    pub(crate) static INSTEAD: Map =
+        include!(concat!(env!("OUT_DIR"), "/fingerprint-instead-gen.rs"));
+}
+
+fn is_fingerprint_of(fingerprinted: &str, stem: &str, ext: &str) -> bool {
+    fingerprinted
+        .strip_prefix(stem)
+        .and_then(|rest| rest.strip_prefix('.'))
+        .and_then(|rest| rest.strip_suffix(ext))
+        .and_then(|rest| rest.strip_suffix('.'))
+        .is_some_and(|hash| hash.len() == 8 && hash.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+#[test]
+fn should_name_fingerprinted_assets_with_digest_prefix() {
+    let name = assets::ALONGSIDE
+        .fingerprinted("js/canary.js")
+        .expect("fingerprinted js/canary.js");
+
+    assert!(is_fingerprint_of(name, "js/canary", "js"), "Name: {}", name);
+}
+
+#[test]
+fn should_serve_both_names_alongside() {
+    let name = assets::ALONGSIDE
+        .fingerprinted("canary.html")
+        .expect("fingerprinted canary.html");
+
+    let logical = assets::ALONGSIDE.get("canary.html").expect("logical");
+    let fingerprinted = assets::ALONGSIDE.get(name).expect("fingerprinted");
+
+    assert_eq!(logical.name, fingerprinted.name);
+    assert_eq!(fingerprinted.fingerprinted_name, Some(name));
+}
+
+#[test]
+fn should_only_serve_fingerprinted_names_instead() {
+    let name = assets::INSTEAD
+        .fingerprinted("canary.html")
+        .expect("fingerprinted canary.html");
+
+    assert!(assets::INSTEAD.get("canary.html").is_none());
+    let asset = assets::INSTEAD.get(name).expect("fingerprinted");
+    assert_eq!(asset.name, "canary.html");
+}
+
+#[test]
+fn should_route_every_served_name() {
+    let alongside = assets::ALONGSIDE
+        .routes()
+        .map(|(name, _)| name)
+        .collect::<BTreeSet<_>>();
+    let instead = assets::INSTEAD
+        .routes()
+        .map(|(name, _)| name)
+        .collect::<BTreeSet<_>>();
+
+    assert_eq!(alongside.len(), 2 * assets::ALONGSIDE.iter().count());
+    assert!(alongside.contains("canary.html"));
+    assert_eq!(instead.len(), assets::INSTEAD.iter().count());
+    assert!(!instead.contains("canary.html"));
+    assert!(instead.is_subset(&alongside));
+}
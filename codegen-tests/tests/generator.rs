@@ -0,0 +1,58 @@
+use std::path::Path;
+
+use static_assets_codegen::{Encoding, Error, Generator, Root};
+
+#[test]
+fn should_resolve_source_against_root() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .canonicalize()
+        .expect("canonicalize");
+
+    let generator = Generator::new("tests").root(Root::Dir(manifest_dir.join("..").join("macros")));
+    let manifest = generator.manifest().expect("manifest");
+
+    assert!(manifest.get("assets/canary.html").is_some());
+}
+
+#[test]
+fn should_only_precompress_with_chosen_encodings() {
+    let manifest = Generator::new("../macros/tests/assets")
+        .compress([Encoding::Brotli])
+        .url_prefix("/static")
+        .manifest()
+        .expect("manifest");
+
+    let entry = manifest.get("css/canary.css").expect("css/canary.css");
+    assert_eq!(entry.url, "/static/css/canary.css");
+    assert_eq!(
+        entry.encodings.keys().copied().collect::<Vec<_>>(),
+        ["br", "identity"]
+    );
+}
+
+#[test]
+fn should_require_a_target_to_write() {
+    let err = Generator::new("../macros/tests/assets")
+        .write()
+        .expect_err("no target");
+
+    assert!(matches!(err, Error::NoTarget), "{:?}", err);
+}
+
+#[test]
+fn should_write_code_and_manifest() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let code = dir.path().join("assets.rs");
+    let manifest = dir.path().join("manifest.json");
+
+    Generator::new("../macros/tests/assets")
+        .target(&code)
+        .manifest_target(&manifest)
+        .write()
+        .expect("write");
+
+    let code = std::fs::read_to_string(code).expect("read code");
+    assert!(code.contains("static_assets :: Map"), "{}", code);
+    let manifest = std::fs::read_to_string(manifest).expect("read manifest");
+    assert!(manifest.contains("\"canary.html\""), "{}", manifest);
+}
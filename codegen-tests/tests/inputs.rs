@@ -0,0 +1,179 @@
+use std::path::{Path, PathBuf};
+
+use static_assets_codegen::{Generator, Inputs, Options, SkipReason};
+
+const ASSETS: &str = "../macros/tests/ignore-assets";
+
+fn relative(paths: &[PathBuf]) -> Vec<String> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .canonicalize()
+        .expect("canonicalize")
+        .join(ASSETS);
+    let mut paths = paths
+        .iter()
+        .map(|path| {
+            path.strip_prefix(&root)
+                .expect("path within asset directory")
+                .to_str()
+                .expect("utf-8 path")
+                .to_owned()
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn should_report_walked_directories_and_embedded_files() {
+    let options = Options {
+        skip_hidden: true,
+        respect_ignore_files: true,
+        ..Default::default()
+    };
+    let (_, inputs) = Generator::new(ASSETS)
+        .options(options)
+        .generate_with_inputs()
+        .expect("generate");
+
+    assert_eq!(relative(&inputs.directories), ["", "nested"]);
+    assert_eq!(relative(&inputs.files), ["index.html", "nested/page.html"]);
+    assert_eq!(
+        relative(&inputs.ignore_files),
+        [
+            ".assetignore",
+            ".gitignore",
+            ".ignore",
+            "nested/.assetignore"
+        ]
+    );
+}
+
+#[test]
+fn should_anchor_ignore_files_in_generated_code() {
+    let options = Options {
+        respect_ignore_files: true,
+        ..Default::default()
+    };
+    let (tokens, _) = Generator::new(ASSETS)
+        .options(options)
+        .generate_with_inputs()
+        .expect("generate");
+
+    assert!(tokens.to_string().contains(".assetignore\")"), "{}", tokens);
+}
+
+fn skipped(inputs: &Inputs, reason: SkipReason) -> Vec<String> {
+    let paths = inputs
+        .skipped
+        .iter()
+        .filter(|skipped| skipped.reason == reason)
+        .map(|skipped| skipped.path.clone())
+        .collect::<Vec<_>>();
+    relative(&paths)
+}
+
+#[test]
+fn should_report_skipped_files() {
+    let options = Options {
+        skip_hidden: true,
+        exclude: vec!["*.bak".into(), "*.tmp".into()],
+        ..Default::default()
+    };
+    let (_, inputs) = Generator::new(ASSETS)
+        .options(options)
+        .generate_with_inputs()
+        .expect("generate");
+
+    assert_eq!(
+        skipped(&inputs, SkipReason::Pattern),
+        ["nested/page.html.bak", "scratch.tmp"]
+    );
+    assert_eq!(
+        skipped(&inputs, SkipReason::Hidden),
+        [
+            ".assetignore",
+            ".gitignore",
+            ".ignore",
+            "nested/.assetignore"
+        ]
+    );
+    assert!(skipped(&inputs, SkipReason::Ignored).is_empty());
+    assert!(inputs.ignore_files.is_empty());
+}
+
+#[test]
+fn should_report_ignored_paths_once() {
+    let options = Options {
+        skip_hidden: true,
+        respect_ignore_files: true,
+        ..Default::default()
+    };
+    let (_, inputs) = Generator::new(ASSETS)
+        .options(options)
+        .generate_with_inputs()
+        .expect("generate");
+
+    assert_eq!(
+        skipped(&inputs, SkipReason::Ignored),
+        ["drafts", "nested/page.html.bak", "notes.txt", "scratch.tmp"]
+    );
+    assert_eq!(skipped(&inputs, SkipReason::Hidden).len(), 4);
+    assert_eq!(
+        inputs.skipped_summary().as_deref(),
+        Some("Skipped 8 paths: 4 hidden, 4 ignored")
+    );
+}
+
+#[test]
+fn should_skip_ignore_files_that_are_not_hidden() {
+    let options = Options {
+        respect_ignore_files: true,
+        ..Default::default()
+    };
+    let (_, inputs) = Generator::new(ASSETS)
+        .options(options)
+        .generate_with_inputs()
+        .expect("generate");
+
+    assert_eq!(
+        skipped(&inputs, SkipReason::Ignored),
+        [
+            ".assetignore",
+            ".gitignore",
+            ".ignore",
+            "drafts",
+            "nested/.assetignore",
+            "nested/page.html.bak",
+            "notes.txt",
+            "scratch.tmp"
+        ]
+    );
+    assert_eq!(relative(&inputs.files), ["index.html", "nested/page.html"]);
+}
+
+// Cargo re-runs a build script when a directory passed to
+// `rerun-if-changed` is modified, which adding a file to it does.
+#[test]
+fn should_track_directory_that_new_files_are_added_to() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let nested = dir.path().join("nested");
+    std::fs::create_dir(&nested).expect("mkdir");
+    std::fs::write(nested.join("a.css"), "a {}").expect("write");
+    let generator = Generator::new(dir.path());
+
+    let (_, before) = generator.generate_with_inputs().expect("generate");
+    assert!(before
+        .directories
+        .iter()
+        .any(|path| path.ends_with("nested")));
+
+    std::fs::write(nested.join("b.css"), "b {}").expect("write");
+    let (tokens, after) = generator.generate_with_inputs().expect("generate");
+
+    assert_eq!(after.files.len(), 2);
+    assert!(
+        tokens.to_string().contains("\"nested/b.css\""),
+        "{}",
+        tokens
+    );
+}
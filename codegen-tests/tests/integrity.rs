@@ -0,0 +1,38 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use sha2::{Digest, Sha384, Sha512};
+
+mod assets {
+    use static_assets::Map;
+
+    pub(crate) static PLAIN: Map = include!(concat!(env!("OUT_DIR"), "/canary-gen.rs"));
+    pub(crate) static WITH_INTEGRITY: Map =
+        include!(concat!(env!("OUT_DIR"), "/fingerprint-alongside-gen.rs"));
+}
+
+#[test]
+fn should_compute_subresource_integrity() {
+    let asset = assets::WITH_INTEGRITY
+        .get("js/canary.js")
+        .expect("asset js/canary.js");
+
+    let expected = format!(
+        "sha384-{} sha512-{}",
+        STANDARD.encode(Sha384::digest(asset.content)),
+        STANDARD.encode(Sha512::digest(asset.content))
+    );
+    assert_eq!(asset.integrity(), Some(expected.as_str()));
+    assert_eq!(
+        asset.integrity_hashes().collect::<Vec<_>>(),
+        expected.split(' ').collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn should_omit_integrity_by_default() {
+    let asset = assets::PLAIN
+        .get("js/canary.js")
+        .expect("asset js/canary.js");
+
+    assert_eq!(asset.integrity(), None);
+    assert_eq!(asset.integrity_hashes().count(), 0);
+}
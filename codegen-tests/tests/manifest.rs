@@ -0,0 +1,54 @@
+use serde_json::Value;
+
+mod assets {
+    use static_assets::Map;
+
+    pub(crate) static ALONGSIDE: Map =
+        include!(concat!(env!("OUT_DIR"), "/fingerprint-alongside-gen.rs"));
+}
+
+const MANIFEST: &str = include_str!(concat!(env!("OUT_DIR"), "/manifest.json"));
+
+#[test]
+fn should_describe_every_asset() {
+    let manifest: Value = serde_json::from_str(MANIFEST).expect("parse manifest");
+    let entries = manifest.as_object().expect("manifest object");
+
+    let names = assets::ALONGSIDE.iter().map(|a| a.name).collect::<Vec<_>>();
+    assert_eq!(
+        entries.keys().map(String::as_str).collect::<Vec<_>>(),
+        names
+    );
+}
+
+#[test]
+fn should_describe_asset_details() {
+    let manifest: Value = serde_json::from_str(MANIFEST).expect("parse manifest");
+    let asset = assets::ALONGSIDE.get("canary.html").expect("canary.html");
+    let entry = &manifest["canary.html"];
+
+    assert_eq!(
+        entry["url"],
+        format!(
+            "/static/{}",
+            asset.fingerprinted_name.expect("fingerprinted")
+        )
+    );
+    assert_eq!(entry["contentType"], "text/html");
+    assert_eq!(entry["size"], 11);
+    assert_eq!(entry["digest"]["hex"].as_str().expect("hex").len(), 64);
+    assert_eq!(
+        entry["digest"]["base64"].as_str().expect("base64").len(),
+        44
+    );
+    assert_eq!(entry["integrity"], asset.integrity().expect("integrity"));
+    assert_eq!(entry["encodings"], serde_json::json!({ "identity": 11 }));
+
+    let css = &manifest["css/canary.css"];
+    assert!(css["encodings"]["gzip"].as_u64().expect("gzip size") < 1508);
+}
+
+#[test]
+fn should_match_runtime_manifest() {
+    assert_eq!(assets::ALONGSIDE.manifest("/static/").to_json(), MANIFEST);
+}
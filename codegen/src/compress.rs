@@ -0,0 +1,62 @@
+use std::io::Write;
+
+use flate2::{write::GzEncoder, Compression};
+use static_assets::Encoding;
+
+const BROTLI_QUALITY: u32 = 11;
+const BROTLI_WINDOW: u32 = 22;
+const BROTLI_BUFFER_SIZE: usize = 4096;
+const ZSTD_LEVEL: i32 = 19;
+
+#[derive(Debug, Default)]
+pub(crate) struct Variants {
+    pub(crate) gzip: Option<Vec<u8>>,
+    pub(crate) brotli: Option<Vec<u8>>,
+    pub(crate) zstd: Option<Vec<u8>>,
+}
+
+impl Variants {
+    pub(crate) fn compress(content: &[u8], encodings: &[Encoding]) -> std::io::Result<Self> {
+        let mut variants = Variants::default();
+        if encodings.contains(&Encoding::Gzip) {
+            variants.gzip = smaller_than(content, gzip(content)?);
+        }
+        if encodings.contains(&Encoding::Brotli) {
+            variants.brotli = smaller_than(content, brotli(content)?);
+        }
+        if encodings.contains(&Encoding::Zstd) {
+            variants.zstd = smaller_than(content, zstd(content)?);
+        }
+        Ok(variants)
+    }
+}
+
+// Only keep a variant when it actually saves space over the original.
+fn smaller_than(content: &[u8], compressed: Vec<u8>) -> Option<Vec<u8>> {
+    if compressed.len() < content.len() {
+        Some(compressed)
+    } else {
+        None
+    }
+}
+
+fn gzip(content: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut enc = GzEncoder::new(Vec::new(), Compression::best());
+    enc.write_all(content)?;
+    enc.finish()
+}
+
+fn brotli(content: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut enc = brotli::CompressorWriter::new(
+        Vec::new(),
+        BROTLI_BUFFER_SIZE,
+        BROTLI_QUALITY,
+        BROTLI_WINDOW,
+    );
+    enc.write_all(content)?;
+    Ok(enc.into_inner())
+}
+
+fn zstd(content: &[u8]) -> std::io::Result<Vec<u8>> {
+    zstd::bulk::compress(content, ZSTD_LEVEL)
+}
@@ -0,0 +1,238 @@
+use std::path::{Path, PathBuf};
+
+use proc_macro2::TokenStream;
+use static_assets::{Encoding, Manifest};
+
+use crate::{
+    collect, describe, render, write_file_if_changed, Error, Fingerprint, Inputs, Integrity,
+    Options,
+};
+
+/// The directory that the asset path is resolved against. Absolute asset
+/// paths are used as-is.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Root {
+    /// `$CARGO_MANIFEST_DIR`, or the current directory if unset.
+    #[default]
+    ManifestDir,
+    /// `$OUT_DIR`, for assets produced earlier in a build script.
+    OutDir,
+    /// A specific directory.
+    Dir(PathBuf),
+}
+
+impl Root {
+    fn resolve(&self, path: &Path) -> Result<PathBuf, Error> {
+        let base = match self {
+            Root::ManifestDir => std::env::var("CARGO_MANIFEST_DIR")
+                .unwrap_or_else(|_| {
+                    eprintln!("Environment variable $CARGO_MANIFEST_DIR not set, assuming \".\"");
+                    ".".into()
+                })
+                .into(),
+            Root::OutDir => std::env::var_os("OUT_DIR")
+                .ok_or(Error::MissingEnvVar("OUT_DIR"))?
+                .into(),
+            Root::Dir(dir) => dir.clone(),
+        };
+        let base = base.canonicalize().map_err(|e| Error::IO(base, e))?;
+        Ok(base.join(path))
+    }
+}
+
+/// Configures how assets are found, processed and written out.
+///
+/// ```no_run
+/// use std::{env, path::PathBuf};
+///
+/// use static_assets_codegen::{Encoding, Fingerprint, Generator};
+///
+/// let out_dir = PathBuf::from(env::var("OUT_DIR").expect("$OUT_DIR"));
+/// Generator::new("static")
+///     .fingerprint(Fingerprint::Alongside)
+///     .compress([Encoding::Brotli])
+///     .exclude("*.map")
+///     .target(out_dir.join("assets.rs"))
+///     .write_for_build_script()
+///     .expect("generate assets");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Generator {
+    path: PathBuf,
+    root: Root,
+    options: Options,
+    url_prefix: String,
+    target: Option<PathBuf>,
+    manifest_target: Option<PathBuf>,
+    report_skipped: bool,
+}
+
+impl Generator {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Generator {
+            path: path.into(),
+            root: Root::default(),
+            options: Options::default(),
+            url_prefix: "/".into(),
+            target: None,
+            manifest_target: None,
+            report_skipped: false,
+        }
+    }
+
+    pub fn root(mut self, root: Root) -> Self {
+        self.root = root;
+        self
+    }
+
+    /// Replaces all processing options at once.
+    pub fn options(mut self, options: Options) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn fingerprint(mut self, fingerprint: Fingerprint) -> Self {
+        self.options.fingerprint = fingerprint;
+        self
+    }
+
+    /// Sets the algorithms used for Subresource Integrity hashes.
+    pub fn integrity(mut self, algorithms: impl IntoIterator<Item = Integrity>) -> Self {
+        self.options.integrity = algorithms.into_iter().collect();
+        self
+    }
+
+    /// Sets the encodings that assets are precompressed with.
+    pub fn compress(mut self, encodings: impl IntoIterator<Item = Encoding>) -> Self {
+        self.options.compress = encodings.into_iter().collect();
+        self
+    }
+
+    /// Adds a glob pattern for files to embed; see [`Options::include`].
+    pub fn include(mut self, pattern: impl Into<String>) -> Self {
+        self.options.include.push(pattern.into());
+        self
+    }
+
+    /// Adds a glob pattern for files to leave out; see [`Options::exclude`].
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.options.exclude.push(pattern.into());
+        self
+    }
+
+    pub fn skip_hidden(mut self, skip_hidden: bool) -> Self {
+        self.options.skip_hidden = skip_hidden;
+        self
+    }
+
+    pub fn respect_ignore_files(mut self, respect_ignore_files: bool) -> Self {
+        self.options.respect_ignore_files = respect_ignore_files;
+        self
+    }
+
+    /// Serves assets under a path; see [`Options::prefix`].
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.options.prefix = prefix.into();
+        self
+    }
+
+    /// Reads assets from disk in debug builds; see [`Options::passthrough`].
+    pub fn passthrough(mut self, passthrough: bool) -> Self {
+        self.options.passthrough = passthrough;
+        self
+    }
+
+    /// Sets the prefix for URLs in the manifest; `/` by default.
+    pub fn url_prefix(mut self, url_prefix: impl Into<String>) -> Self {
+        self.url_prefix = url_prefix.into();
+        self
+    }
+
+    /// Sets the file that [`Generator::write`] writes generated code to.
+    pub fn target(mut self, target: impl Into<PathBuf>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Sets the file that [`Generator::write`] writes the JSON manifest to.
+    pub fn manifest_target(mut self, target: impl Into<PathBuf>) -> Self {
+        self.manifest_target = Some(target.into());
+        self
+    }
+
+    /// Whether [`Generator::write_for_build_script`] warns with a count of
+    /// the paths left out for each reason; off by default.
+    pub fn report_skipped(mut self, report_skipped: bool) -> Self {
+        self.report_skipped = report_skipped;
+        self
+    }
+
+    /// The asset directory, resolved against the root.
+    pub fn source_dir(&self) -> Result<PathBuf, Error> {
+        self.root.resolve(&self.path)
+    }
+
+    /// Generates an expression that evaluates to a `static_assets::Map`.
+    pub fn generate(&self) -> Result<TokenStream, Error> {
+        self.generate_with_inputs().map(|(tokens, _)| tokens)
+    }
+
+    /// Like [`Generator::generate`], but also returns the paths that were
+    /// read.
+    pub fn generate_with_inputs(&self) -> Result<(TokenStream, Inputs), Error> {
+        let dir = self.source_dir()?;
+        let (files, inputs) = collect(&dir, &self.options)?;
+        let tokens = render(&dir, &files, &inputs, &self.options)?;
+        Ok((tokens, inputs))
+    }
+
+    /// Describes the assets that would be embedded.
+    pub fn manifest(&self) -> Result<Manifest, Error> {
+        let (files, _) = collect(&self.source_dir()?, &self.options)?;
+        Ok(describe(&files, &self.url_prefix))
+    }
+
+    /// Writes generated code and the manifest to whichever targets are set,
+    /// only touching files whose content changes.
+    pub fn write(&self) -> Result<Inputs, Error> {
+        if self.target.is_none() && self.manifest_target.is_none() {
+            return Err(Error::NoTarget);
+        }
+
+        let dir = self.source_dir()?;
+        let (files, inputs) = collect(&dir, &self.options)?;
+        if let Some(target) = &self.target {
+            let tokens = render(&dir, &files, &inputs, &self.options)?;
+            write_file_if_changed(target, &tokens.to_string())?;
+        }
+        if let Some(target) = &self.manifest_target {
+            write_file_if_changed(target, &describe(&files, &self.url_prefix).to_json())?;
+        }
+
+        Ok(inputs)
+    }
+
+    /// Like [`Generator::write`], but also tells cargo to re-run the build
+    /// script when the asset directory, any directory beneath it, or any
+    /// embedded file changes. See [`Generator::report_skipped`] for a
+    /// summary of what was left out.
+    pub fn write_for_build_script(&self) -> Result<(), Error> {
+        let inputs = self.write()?;
+
+        for path in inputs
+            .directories
+            .iter()
+            .chain(&inputs.files)
+            .chain(&inputs.ignore_files)
+        {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+        if self.report_skipped {
+            if let Some(summary) = inputs.skipped_summary() {
+                println!("cargo:warning={}", summary);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -3,92 +3,545 @@ use std::fs::read_to_string;
 use std::io::{ErrorKind, Write};
 use std::path::{Path, PathBuf};
 
-use blake2::{Blake2s256, Digest};
-use proc_macro2::TokenStream;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use proc_macro2::{Literal, TokenStream};
 use quote::{quote, ToTokens};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use static_assets::{metadata, Asset, Manifest};
 use thiserror::Error;
 
+use crate::compress::Variants;
+
+mod compress;
+mod generator;
+
+pub use generator::{Generator, Root};
+pub use static_assets::Encoding;
+
 #[derive(Error, Debug)]
 pub enum Error {
-    #[error("I/O")]
-    IO(#[from] std::io::Error),
-    #[error("Walking directory tree")]
-    WalkDir(#[from] walkdir::Error),
+    #[error("I/O error on {0}")]
+    IO(PathBuf, #[source] std::io::Error),
+    #[error("Walking directory tree {0}")]
+    Walk(PathBuf, #[source] ignore::Error),
+    #[error("Expected {0} to be a directory")]
+    NotADirectory(PathBuf),
     #[error("Non-utf8 path")]
     NonUtf8Path(PathBuf),
     #[error("Expected directory {0} to contain found file {1}")]
     FoundFileNotInSourceDirectory(PathBuf, PathBuf),
     #[error("Cannot find parent directory for target: {0}")]
     NoParentDirectory(PathBuf),
-    #[error("Persisting temporary file")]
-    PersistTempFile(#[from] tempfile::PersistError),
+    #[error("Persisting temporary file to {0}")]
+    PersistTempFile(PathBuf, #[source] tempfile::PersistError),
+    #[error("Invalid glob pattern")]
+    Glob(#[from] globset::Error),
+    #[error("Environment variable ${0} not set")]
+    MissingEnvVar(&'static str),
+    #[error("No output target configured")]
+    NoTarget,
 }
 
-fn root_dir() -> Result<PathBuf, Error> {
-    let base = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| {
-        eprintln!("Environment variable $CARGO_MANIFEST_DIR not set, assuming \".\"");
-        ".".into()
-    });
-    Ok(PathBuf::from(base).canonicalize()?)
+// Ignore files with gitignore syntax that only apply to asset generation.
+const ASSET_IGNORE_FILENAME: &str = ".assetignore";
+const IGNORE_FILENAMES: [&str; 3] = [".gitignore", ".ignore", ASSET_IGNORE_FILENAME];
+
+// The number of digest bytes included in fingerprinted names.
+const FINGERPRINT_BYTES: usize = 4;
+
+/// How assets are named in the generated `Map`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Fingerprint {
+    /// Serve assets under their path relative to the asset directory.
+    #[default]
+    Off,
+    /// Also serve each asset under a name including a prefix of its
+    /// digest, eg: `app.3f9a1c2b.js` for `app.js`.
+    Alongside,
+    /// Only serve assets under their fingerprinted names.
+    Instead,
 }
 
-pub fn generate(path: &Path) -> Result<TokenStream, Error> {
-    let dir = root_dir()?.join(path);
+/// A hash algorithm for Subresource Integrity strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Integrity {
+    Sha256,
+    Sha384,
+    Sha512,
+}
 
-    let mut files = BTreeSet::new();
-    for entry in walkdir::WalkDir::new(&dir) {
-        let entry = entry?;
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub fingerprint: Fingerprint,
+    /// Algorithms to compute Subresource Integrity hashes with; none by
+    /// default.
+    pub integrity: Vec<Integrity>,
+    /// Glob patterns matched against each file's path relative to the asset
+    /// directory. When non-empty, only files matching one of these are
+    /// embedded.
+    pub include: Vec<String>,
+    /// Glob patterns for files to leave out, even if they are included.
+    pub exclude: Vec<String>,
+    /// Whether to leave out files and directories whose name starts with a
+    /// `.`, such as `.DS_Store`.
+    pub skip_hidden: bool,
+    /// Whether to leave out files matched by `.gitignore`, `.ignore` or
+    /// `.assetignore` files within the asset directory.
+    pub respect_ignore_files: bool,
+    /// Encodings to precompress assets with; each variant is only kept when
+    /// it is smaller than the original. All are enabled by default.
+    pub compress: Vec<Encoding>,
+    /// A path to serve assets under, eg: `assets` serves `app.js` as
+    /// `assets/app.js`. Patterns are still matched against the unprefixed
+    /// name.
+    pub prefix: String,
+    /// Whether debug builds should read assets from the asset directory on
+    /// each lookup, rather than using the embedded copies. Requires the
+    /// `passthrough` feature of `static-assets`.
+    pub passthrough: bool,
+}
 
-        if entry.file_type().is_file() {
-            let name = entry.path().to_path_buf();
-            files.insert(name);
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            fingerprint: Fingerprint::default(),
+            integrity: Vec::new(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            skip_hidden: false,
+            respect_ignore_files: false,
+            compress: vec![Encoding::Gzip, Encoding::Brotli, Encoding::Zstd],
+            prefix: String::new(),
+            passthrough: false,
         }
     }
+}
+
+/// The paths read while generating, so that callers can ask to be re-run
+/// when they change.
+#[derive(Debug, Clone, Default)]
+pub struct Inputs {
+    /// The asset directory, and every directory beneath it that was walked.
+    pub directories: Vec<PathBuf>,
+    /// Files that were embedded.
+    pub files: Vec<PathBuf>,
+    /// Ignore files that were consulted.
+    pub ignore_files: Vec<PathBuf>,
+    /// Files and directories within the asset directory that were left
+    /// out, and why. A directory that was left out is listed on its own,
+    /// rather than each file within it.
+    pub skipped: Vec<Skipped>,
+}
+
+/// A path that was not embedded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Skipped {
+    pub path: PathBuf,
+    pub reason: SkipReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// Its name starts with a `.`, and [`Options::skip_hidden`] is set.
+    Hidden,
+    /// It matched a rule in a `.gitignore`, `.ignore` or `.assetignore`
+    /// file, or is one of those files.
+    Ignored,
+    /// It didn't match the `include` patterns, or matched an `exclude`
+    /// pattern.
+    Pattern,
+}
+
+impl Inputs {
+    /// Counts the skipped paths for each reason, eg: `Skipped 3 paths: 1
+    /// hidden, 2 excluded by patterns`.
+    pub fn skipped_summary(&self) -> Option<String> {
+        if self.skipped.is_empty() {
+            return None;
+        }
+
+        let counts = [
+            (SkipReason::Hidden, "hidden"),
+            (SkipReason::Ignored, "ignored"),
+            (SkipReason::Pattern, "excluded by patterns"),
+        ]
+        .into_iter()
+        .filter_map(|(reason, label)| {
+            let count = self.skipped.iter().filter(|s| s.reason == reason).count();
+            (count > 0).then(|| format!("{} {}", count, label))
+        })
+        .collect::<Vec<_>>();
+        Some(format!(
+            "Skipped {} paths: {}",
+            self.skipped.len(),
+            counts.join(", ")
+        ))
+    }
+}
+
+impl Integrity {
+    fn hash(&self, content: &[u8]) -> String {
+        let (prefix, digest) = match self {
+            Integrity::Sha256 => ("sha256", Sha256::digest(content).to_vec()),
+            Integrity::Sha384 => ("sha384", Sha384::digest(content).to_vec()),
+            Integrity::Sha512 => ("sha512", Sha512::digest(content).to_vec()),
+        };
+        format!("{}-{}", prefix, STANDARD.encode(digest))
+    }
+}
+
+#[deprecated(note = "use `Generator::new(path).generate()`")]
+pub fn generate(path: &Path) -> Result<TokenStream, Error> {
+    Generator::new(path).generate()
+}
 
+fn render(
+    dir: &Path,
+    files: &[File],
+    inputs: &Inputs,
+    options: &Options,
+) -> Result<TokenStream, Error> {
     let mut members = TokenStream::new();
-    for path in files {
-        let pathname = path
-            .to_str()
-            .ok_or_else(|| Error::NonUtf8Path(path.to_owned()))?;
-        let name = path
-            .strip_prefix(&dir)
-            .map_err(|_| Error::FoundFileNotInSourceDirectory(dir.to_owned(), path.to_owned()))?
-            .to_str()
-            .ok_or_else(|| Error::NonUtf8Path(path.to_owned()))?;
+    let mut fingerprints = Vec::new();
+    for (idx, file) in files.iter().enumerate() {
+        let File {
+            pathname,
+            name,
+            content_type,
+            digest,
+            integrity,
+            fingerprinted_name,
+            variants,
+            ..
+        } = file;
 
-        let content_type = mime_guess::from_path(&path)
-            .first_or_octet_stream()
-            .to_string();
+        let digest_bytes = digest.iter().map(|b| quote!(#b,)).collect::<TokenStream>();
 
-        let mut hasher = Blake2s256::default();
-        hasher.update(std::fs::read(&path)?);
-        let digest_bytes = hasher
-            .finalize()
-            .iter()
-            .map(|b| quote!(#b,))
-            .collect::<TokenStream>();
+        let fingerprinted_name = match fingerprinted_name {
+            Some(fingerprinted) => {
+                fingerprints.push((fingerprinted, idx));
+                quote!(::core::option::Option::Some(#fingerprinted))
+            }
+            None => quote!(::core::option::Option::None),
+        };
+
+        let integrity = match integrity {
+            Some(integrity) => quote!(::core::option::Option::Some(#integrity)),
+            None => quote!(::core::option::Option::None),
+        };
+
+        let gzip = option_bytes(variants.gzip.as_deref());
+        let brotli = option_bytes(variants.brotli.as_deref());
+        let zstd = option_bytes(variants.zstd.as_deref());
 
         let asset = quote!(::static_assets::Asset {
             name: #name,
+            fingerprinted_name: #fingerprinted_name,
             content: include_bytes!(#pathname),
             content_type: #content_type,
             digest: &[#digest_bytes],
+            integrity: #integrity,
+            gzip: #gzip,
+            brotli: #brotli,
+            zstd: #zstd,
         });
 
         quote!(#asset,).to_tokens(&mut members)
     }
 
-    let out = quote!(
-       ::static_assets::Map{ members: &[#members]}
+    fingerprints.sort();
+    let fingerprints = fingerprints
+        .into_iter()
+        .map(|(name, idx)| quote!((#name, #idx),))
+        .collect::<TokenStream>();
+    let logical_names = options.fingerprint != Fingerprint::Instead;
+
+    // Embedded files are already tracked by `include_bytes!`, but ignore
+    // files also affect the output, so we include them as anchors.
+    let anchors = inputs
+        .ignore_files
+        .iter()
+        .map(|path| {
+            let pathname = path
+                .to_str()
+                .ok_or_else(|| Error::NonUtf8Path(path.to_owned()))?;
+            Ok(quote!(
+                const _: &[u8] = include_bytes!(#pathname);
+            ))
+        })
+        .collect::<Result<TokenStream, Error>>()?;
+
+    let source_dir = if options.passthrough {
+        let dir = dir
+            .to_str()
+            .ok_or_else(|| Error::NonUtf8Path(dir.to_owned()))?;
+        let prefix = options.prefix.trim_matches('/');
+        quote!(if cfg!(debug_assertions) {
+            ::core::option::Option::Some(::static_assets::SourceDir {
+                path: #dir,
+                prefix: #prefix,
+            })
+        } else {
+            ::core::option::Option::None
+        })
+    } else {
+        quote!(::core::option::Option::None)
+    };
+
+    let map = quote!(
+       ::static_assets::Map::new(
+           &[#members],
+           &[#fingerprints],
+           #logical_names,
+           #source_dir,
+       )
     );
+    let out = if anchors.is_empty() {
+        map
+    } else {
+        quote!({ #anchors #map })
+    };
 
     Ok(out)
 }
 
-pub fn generate_to_file(assets_path: &std::path::Path, target: PathBuf) -> Result<(), Error> {
-    let content = generate(assets_path)?;
+fn describe(files: &[File], url_prefix: &str) -> Manifest {
+    let assets = files.iter().map(|file| Asset {
+        name: &file.name,
+        fingerprinted_name: file.fingerprinted_name.as_deref(),
+        content: &file.content,
+        content_type: &file.content_type,
+        digest: &file.digest,
+        integrity: file.integrity.as_deref(),
+        gzip: file.variants.gzip.as_deref(),
+        brotli: file.variants.brotli.as_deref(),
+        zstd: file.variants.zstd.as_deref(),
+    });
+
+    Manifest::new(assets, url_prefix)
+}
+
+// Everything we need to know about a file to embed it.
+struct File {
+    pathname: String,
+    name: String,
+    content_type: String,
+    content: Vec<u8>,
+    digest: Vec<u8>,
+    integrity: Option<String>,
+    fingerprinted_name: Option<String>,
+    variants: Variants,
+}
+
+fn collect(dir: &Path, options: &Options) -> Result<(Vec<File>, Inputs), Error> {
+    // Checked up front, as walk errors for the root are rather repetitive.
+    let metadata = std::fs::metadata(dir).map_err(|e| Error::IO(dir.to_owned(), e))?;
+    if !metadata.is_dir() {
+        return Err(Error::NotADirectory(dir.to_owned()));
+    }
+
+    let include = glob_set(&options.include)?;
+    let exclude = glob_set(&options.exclude)?;
+
+    let mut walker = ignore::WalkBuilder::new(dir);
+    walker
+        .standard_filters(false)
+        .hidden(options.skip_hidden)
+        .git_ignore(options.respect_ignore_files)
+        .ignore(options.respect_ignore_files)
+        .require_git(false);
+    if options.respect_ignore_files {
+        walker.add_custom_ignore_filename(ASSET_IGNORE_FILENAME);
+    }
+
+    let mut inputs = Inputs::default();
+    let mut paths = BTreeSet::new();
+    let mut walked = BTreeSet::new();
+    for entry in walker.build() {
+        let entry = entry.map_err(|e| Error::Walk(dir.to_owned(), e))?;
+        walked.insert(entry.path().to_owned());
+        let file_type = entry.file_type();
+
+        if file_type.is_some_and(|ty| ty.is_file()) {
+            // Ignore files would give away what they hide, so they are never
+            // embedded while they're in effect.
+            let is_ignore_file = options.respect_ignore_files
+                && entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| IGNORE_FILENAMES.contains(&name));
+            if is_ignore_file {
+                inputs.skipped.push(Skipped {
+                    path: entry.into_path(),
+                    reason: SkipReason::Ignored,
+                });
+            } else {
+                paths.insert(entry.into_path());
+            }
+        } else if file_type.is_some_and(|ty| ty.is_dir()) {
+            if options.respect_ignore_files {
+                for ignore_file in IGNORE_FILENAMES {
+                    let ignore_file = entry.path().join(ignore_file);
+                    if ignore_file.is_file() {
+                        inputs.ignore_files.push(ignore_file);
+                    }
+                }
+            }
+            inputs.directories.push(entry.into_path());
+        }
+    }
+
+    // The walker leaves out hidden and ignored entries silently, so we look
+    // for them among the children of each directory it visited.
+    for walked_dir in &inputs.directories {
+        let entries =
+            std::fs::read_dir(walked_dir).map_err(|e| Error::IO(walked_dir.to_owned(), e))?;
+        for entry in entries {
+            let path = entry
+                .map_err(|e| Error::IO(walked_dir.to_owned(), e))?
+                .path();
+            if walked.contains(&path) {
+                continue;
+            }
+            let hidden = path
+                .file_name()
+                .is_some_and(|name| name.as_encoded_bytes().starts_with(b"."));
+            let reason = if options.skip_hidden && hidden {
+                SkipReason::Hidden
+            } else {
+                SkipReason::Ignored
+            };
+            inputs.skipped.push(Skipped { path, reason });
+        }
+    }
+
+    let mut files = Vec::new();
+    for path in paths {
+        let pathname = path
+            .to_str()
+            .ok_or_else(|| Error::NonUtf8Path(path.to_owned()))?;
+        let name = path
+            .strip_prefix(dir)
+            .map_err(|_| Error::FoundFileNotInSourceDirectory(dir.to_owned(), path.to_owned()))?
+            .to_str()
+            .ok_or_else(|| Error::NonUtf8Path(path.to_owned()))?;
+
+        let included = include.as_ref().is_none_or(|set| set.is_match(name));
+        let excluded = exclude.as_ref().is_some_and(|set| set.is_match(name));
+        if !included || excluded {
+            inputs.skipped.push(Skipped {
+                path,
+                reason: SkipReason::Pattern,
+            });
+            continue;
+        }
+
+        let prefix = options.prefix.trim_matches('/');
+        let name = if prefix.is_empty() {
+            name.to_owned()
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+
+        let content_type = metadata::content_type(pathname);
+
+        let content = std::fs::read(&path).map_err(|e| Error::IO(path.clone(), e))?;
+
+        let digest = metadata::digest(&content);
+
+        let fingerprinted_name = match options.fingerprint {
+            Fingerprint::Off => None,
+            Fingerprint::Alongside | Fingerprint::Instead => {
+                Some(fingerprinted_name(&name, &digest))
+            }
+        };
 
-    write_file_if_changed(&target, &format!("{}", content))?;
+        let integrity = if options.integrity.is_empty() {
+            None
+        } else {
+            let hashes = options
+                .integrity
+                .iter()
+                .map(|alg| alg.hash(&content))
+                .collect::<Vec<_>>();
+            Some(hashes.join(" "))
+        };
+
+        let variants = Variants::compress(&content, &options.compress)
+            .map_err(|e| Error::IO(path.clone(), e))?;
+
+        inputs.files.push(path.clone());
+        files.push(File {
+            pathname: pathname.to_owned(),
+            name,
+            content_type,
+            content,
+            digest,
+            integrity,
+            fingerprinted_name,
+            variants,
+        });
+    }
+
+    inputs.skipped.sort_by(|a, b| a.path.cmp(&b.path));
+
+    // Paths sort by component, so `a/b.css` precedes `a-b.css`, but `Map`
+    // searches by comparing names as strings.
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok((files, inputs))
+}
+
+fn glob_set(patterns: &[String]) -> Result<Option<GlobSet>, Error> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern)?);
+    }
+    Ok(Some(builder.build()?))
+}
+
+// Inserts a prefix of the digest before the file's extension, so that
+// `js/app.min.js` becomes `js/app.min.3f9a1c2b.js`.
+fn fingerprinted_name(name: &str, digest: &[u8]) -> String {
+    let hash = digest
+        .iter()
+        .take(FINGERPRINT_BYTES)
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+
+    let (dir, file) = match name.rsplit_once('/') {
+        Some((dir, file)) => (Some(dir), file),
+        None => (None, name),
+    };
+    let file = match file.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{}.{}.{}", stem, hash, ext),
+        _ => format!("{}.{}", file, hash),
+    };
+
+    match dir {
+        Some(dir) => format!("{}/{}", dir, file),
+        None => file,
+    }
+}
+
+fn option_bytes(bytes: Option<&[u8]>) -> TokenStream {
+    match bytes {
+        Some(bytes) => {
+            let lit = Literal::byte_string(bytes);
+            quote!(::core::option::Option::Some(#lit as &[u8]))
+        }
+        None => quote!(::core::option::Option::None),
+    }
+}
+
+#[deprecated(note = "use `Generator::new(assets_path).target(target).write()`")]
+pub fn generate_to_file(assets_path: &std::path::Path, target: PathBuf) -> Result<(), Error> {
+    Generator::new(assets_path).target(target).write()?;
 
     Ok(())
 }
@@ -101,15 +554,18 @@ fn write_file_if_changed(target: &Path, content: &str) -> Result<(), Error> {
             }
         }
         Err(error) if error.kind() == ErrorKind::NotFound => {}
-        Err(error) => return Err(Error::from(error)),
+        Err(error) => return Err(Error::IO(target.to_owned(), error)),
     };
 
     let dir = target
         .parent()
         .ok_or_else(|| Error::NoParentDirectory(target.into()))?;
 
-    let mut tmpf = tempfile::NamedTempFile::new_in(dir)?;
-    tmpf.write_all(content.as_bytes())?;
-    tmpf.persist(target)?;
+    let mut tmpf =
+        tempfile::NamedTempFile::new_in(dir).map_err(|e| Error::IO(dir.to_owned(), e))?;
+    tmpf.write_all(content.as_bytes())
+        .map_err(|e| Error::IO(tmpf.path().to_owned(), e))?;
+    tmpf.persist(target)
+        .map_err(|e| Error::PersistTempFile(target.to_owned(), e))?;
     Ok(())
 }
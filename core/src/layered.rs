@@ -0,0 +1,107 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::{AssetRef, AssetSource};
+
+/// Stacks several sources, eg: deployment overrides on top of an embedded
+/// theme. Lookups are answered by the first layer with an asset under that
+/// name.
+///
+/// ```
+/// use static_assets::{Layered, OwnedMap};
+///
+/// # fn main() -> Result<(), static_assets::BuildError> {
+/// let base = OwnedMap::builder().asset(logo("base")).build()?;
+/// let custom = OwnedMap::builder().asset(logo("custom")).build()?;
+/// let assets = Layered::new().layer("custom", custom).layer("base", base);
+///
+/// let (layer, asset) = assets.locate("logo.svg").expect("logo");
+/// assert_eq!(layer, "custom");
+/// assert_eq!(asset.asset().content, b"custom");
+/// # Ok(())
+/// # }
+/// # fn logo(content: &str) -> static_assets::OwnedAsset {
+/// #     static_assets::OwnedAsset {
+/// #         name: "logo.svg".into(),
+/// #         fingerprinted_name: None,
+/// #         content: content.into(),
+/// #         content_type: "image/svg+xml".into(),
+/// #         digest: vec![],
+/// #         integrity: None,
+/// #         gzip: None,
+/// #         brotli: None,
+/// #         zstd: None,
+/// #     }
+/// # }
+/// ```
+#[derive(Default)]
+pub struct Layered {
+    /// Highest precedence first.
+    layers: Vec<(String, Box<dyn AssetSource + Send + Sync>)>,
+}
+
+impl Layered {
+    pub fn new() -> Self {
+        Layered::default()
+    }
+
+    /// Adds a layer beneath those already added, so it is only consulted
+    /// for names that none of them have.
+    pub fn layer(
+        mut self,
+        name: impl Into<String>,
+        source: impl AssetSource + Send + Sync + 'static,
+    ) -> Self {
+        self.layers.push((name.into(), Box::new(source)));
+        self
+    }
+
+    /// Looks up an asset, along with the name of the layer that provided it.
+    pub fn locate(&self, name: &str) -> Option<(&str, AssetRef)> {
+        self.layers
+            .iter()
+            .find_map(|(layer, source)| Some((layer.as_str(), source.get(name)?)))
+    }
+
+    /// Iterates over each asset that isn't shadowed by one with the same
+    /// logical name in a higher layer, along with the name of its layer.
+    pub fn provenance(&self) -> impl Iterator<Item = (&str, AssetRef)> + '_ {
+        let mut seen = HashSet::new();
+        self.layers
+            .iter()
+            .flat_map(|(layer, source)| source.iter().map(move |asset| (layer.as_str(), asset)))
+            .filter(move |(_, asset)| seen.insert(asset.asset().name.to_owned()))
+    }
+}
+
+impl AssetSource for Layered {
+    fn get(&self, name: &str) -> Option<AssetRef> {
+        self.locate(name).map(|(_, asset)| asset)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = AssetRef> + '_> {
+        Box::new(self.provenance().map(|(_, asset)| asset))
+    }
+
+    fn names(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        let mut seen = HashSet::new();
+        Box::new(
+            self.layers
+                .iter()
+                .flat_map(|(_, source)| source.names())
+                .filter(move |name| seen.insert(name.clone().into_owned())),
+        )
+    }
+}
+
+impl fmt::Debug for Layered {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Layered")
+            .field(
+                "layers",
+                &self.layers.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
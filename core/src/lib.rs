@@ -1,19 +1,206 @@
+mod encoding;
+mod layered;
+#[cfg(feature = "manifest")]
+pub mod manifest;
+#[cfg(feature = "metadata")]
+pub mod metadata;
+#[cfg(feature = "metadata")]
+mod overlay;
+mod owned;
+#[cfg(feature = "passthrough")]
+mod passthrough;
+mod source;
+
+pub use encoding::Encoding;
+pub use layered::Layered;
+#[cfg(feature = "manifest")]
+pub use manifest::Manifest;
+#[cfg(feature = "metadata")]
+pub use overlay::Overlay;
+pub use owned::{BuildError, MapBuilder, OwnedMap};
+pub use source::{AssetRef, AssetSource, OwnedAsset};
+
 #[derive(Debug, Clone)]
 pub struct Asset<'a> {
     pub name: &'a str,
+    pub fingerprinted_name: Option<&'a str>,
     pub content: &'a [u8],
     pub content_type: &'a str,
     pub digest: &'a [u8],
+    /// Space separated Subresource Integrity hashes, eg:
+    /// `sha384-<base64 digest>`.
+    pub integrity: Option<&'a str>,
+    pub gzip: Option<&'a [u8]>,
+    pub brotli: Option<&'a [u8]>,
+    pub zstd: Option<&'a [u8]>,
 }
 
+/// Prefer [`Map::new`] over a struct literal, as it checks the ordering.
 pub struct Map<'a> {
+    /// Sorted by logical name, comparing bytes.
     pub members: &'a [Asset<'a>],
+    /// Pairs of fingerprinted name and index into `members`, sorted by the
+    /// fingerprinted name.
+    pub fingerprinted: &'a [(&'a str, usize)],
+    /// Whether assets may be fetched by their logical name, as well as any
+    /// fingerprinted name.
+    pub logical_names: bool,
+    /// Where assets were embedded from. With the `passthrough` feature,
+    /// assets are read from here on each lookup, so that edits show up
+    /// without recompiling. Only the content and digest are refreshed;
+    /// precompressed variants and integrity hashes are dropped, as they
+    /// would be stale.
+    pub source_dir: Option<SourceDir<'a>>,
+}
+
+/// The directory a `Map` was embedded from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceDir<'a> {
+    pub path: &'a str,
+    /// The path that assets are served under, which their names start with,
+    /// but their paths within the directory don't; empty if none.
+    pub prefix: &'a str,
+}
+
+pub struct MapIter<'a> {
+    members: ::std::slice::Iter<'a, Asset<'a>>,
+    source_dir: Option<SourceDir<'a>>,
 }
 
-pub struct MapIter<'a>(::std::slice::Iter<'a, Asset<'a>>);
+impl<'a> Asset<'a> {
+    /// Returns the content in the given encoding, if a variant was stored.
+    /// `Identity` is always available.
+    pub fn encoded(&self, encoding: Encoding) -> Option<&'a [u8]> {
+        match encoding {
+            Encoding::Identity => Some(self.content),
+            Encoding::Gzip => self.gzip,
+            Encoding::Brotli => self.brotli,
+            Encoding::Zstd => self.zstd,
+        }
+    }
+
+    /// A value for the `integrity` attribute of `<script>` and `<link>`
+    /// tags, if any hashes were computed.
+    pub fn integrity(&self) -> Option<&'a str> {
+        self.integrity
+    }
+
+    /// Each Subresource Integrity hash on its own, eg: to pick a specific
+    /// algorithm.
+    pub fn integrity_hashes(&self) -> impl Iterator<Item = &'a str> {
+        self.integrity.unwrap_or_default().split_whitespace()
+    }
+
+    pub fn encodings(&self) -> impl Iterator<Item = Encoding> + '_ {
+        Encoding::ALL
+            .into_iter()
+            .filter(|&enc| self.encoded(enc).is_some())
+    }
+}
+
+/// Why a `Map` can't be searched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderError {
+    /// Members aren't in ascending order of name, or a name is repeated.
+    Members,
+    /// Fingerprinted names aren't in ascending order, or one is repeated.
+    Fingerprinted,
+    /// A fingerprinted name refers past the end of the members.
+    Index,
+}
 
 impl<'a> Map<'a> {
+    /// Builds a map, checking that names are ordered as lookups expect. In a
+    /// `static` initializer, a mis-ordered map fails to compile.
+    pub const fn new(
+        members: &'a [Asset<'a>],
+        fingerprinted: &'a [(&'a str, usize)],
+        logical_names: bool,
+        source_dir: Option<SourceDir<'a>>,
+    ) -> Self {
+        let map = Map {
+            members,
+            fingerprinted,
+            logical_names,
+            source_dir,
+        };
+        match map.check() {
+            Ok(()) => map,
+            Err(OrderError::Members) => panic!("Map members must be sorted by unique name"),
+            Err(OrderError::Fingerprinted) => {
+                panic!("Map fingerprinted names must be sorted and unique")
+            }
+            Err(OrderError::Index) => panic!("Map fingerprinted index out of range"),
+        }
+    }
+
+    /// Checks that members and fingerprinted names are in strictly
+    /// ascending order of their bytes, as `get` relies on.
+    pub const fn check(&self) -> Result<(), OrderError> {
+        let mut idx = 1;
+        while idx < self.members.len() {
+            if !str_lt(self.members[idx - 1].name, self.members[idx].name) {
+                return Err(OrderError::Members);
+            }
+            idx += 1;
+        }
+
+        let mut idx = 0;
+        while idx < self.fingerprinted.len() {
+            if idx > 0 && !str_lt(self.fingerprinted[idx - 1].0, self.fingerprinted[idx].0) {
+                return Err(OrderError::Fingerprinted);
+            }
+            if self.fingerprinted[idx].1 >= self.members.len() {
+                return Err(OrderError::Index);
+            }
+            idx += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Looks up an asset by any name it is served under.
     pub fn get(&self, s: &str) -> Option<&'a Asset<'a>> {
+        let logical = if self.logical_names {
+            self.get_logical(s)
+        } else {
+            None
+        };
+
+        logical
+            .or_else(|| {
+                self.fingerprinted
+                    .binary_search_by_key(&s, |&(name, _)| name)
+                    .ok()
+                    .map(|idx| &self.members[self.fingerprinted[idx].1])
+            })
+            .map(|asset| live(self.source_dir, asset))
+    }
+
+    /// Returns the fingerprinted name of the asset with the given logical
+    /// name, if it has one.
+    pub fn fingerprinted(&self, logical: &str) -> Option<&'a str> {
+        self.get_logical(logical)?.fingerprinted_name
+    }
+
+    /// Iterates over every name that `get` will find an asset under, along
+    /// with that asset.
+    pub fn routes(&self) -> impl Iterator<Item = (&'a str, &'a Asset<'a>)> {
+        let members = self.members;
+        let source_dir = self.source_dir;
+        let logical: &'a [Asset<'a>] = if self.logical_names { members } else { &[] };
+        let fingerprinted = self
+            .fingerprinted
+            .iter()
+            .map(move |&(name, idx)| (name, &members[idx]));
+        logical
+            .iter()
+            .map(|asset| (asset.name, asset))
+            .chain(fingerprinted)
+            .map(move |(name, asset)| (name, live(source_dir, asset)))
+    }
+
+    fn get_logical(&self, s: &str) -> Option<&'a Asset<'a>> {
         self.members
             .binary_search_by_key(&s, |a| a.name)
             .ok()
@@ -21,7 +208,10 @@ impl<'a> Map<'a> {
     }
 
     pub fn iter(&self) -> MapIter<'a> {
-        MapIter(self.members.iter())
+        MapIter {
+            members: self.members.iter(),
+            source_dir: self.source_dir,
+        }
     }
 }
 
@@ -29,6 +219,33 @@ impl<'a> Iterator for MapIter<'a> {
     type Item = Asset<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.next().cloned()
+        let asset = self.members.next()?;
+        Some(live(self.source_dir, asset).clone())
     }
 }
+
+// Whether `a` sorts strictly before `b`, as `str`'s `Ord` would have it.
+const fn str_lt(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let mut idx = 0;
+    while idx < a.len() && idx < b.len() {
+        if a[idx] != b[idx] {
+            return a[idx] < b[idx];
+        }
+        idx += 1;
+    }
+    a.len() < b.len()
+}
+
+#[cfg(feature = "passthrough")]
+fn live<'a>(source_dir: Option<SourceDir<'_>>, asset: &'a Asset<'a>) -> &'a Asset<'a> {
+    match source_dir {
+        Some(dir) => passthrough::load(dir, asset),
+        None => asset,
+    }
+}
+
+#[cfg(not(feature = "passthrough"))]
+fn live<'a>(_source_dir: Option<SourceDir<'_>>, asset: &'a Asset<'a>) -> &'a Asset<'a> {
+    asset
+}
@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Serialize;
+
+use crate::{Asset, Encoding, Map};
+
+/// A description of a set of assets, keyed by logical name, suitable for
+/// front-end tooling and deployment scripts.
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct Manifest {
+    entries: BTreeMap<String, Entry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Entry {
+    /// The URL to reference the asset by; its fingerprinted name if it has
+    /// one.
+    pub url: String,
+    pub content_type: String,
+    pub size: usize,
+    pub digest: Digest,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub integrity: Option<String>,
+    /// The size of each stored variant, keyed by content-coding.
+    pub encodings: BTreeMap<&'static str, usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Digest {
+    pub hex: String,
+    pub base64: String,
+}
+
+impl Manifest {
+    /// Describes the given assets, with URLs relative to `url_prefix`, eg:
+    /// `/static`.
+    pub fn new<'a, I>(assets: I, url_prefix: &str) -> Self
+    where
+        I: IntoIterator<Item = Asset<'a>>,
+    {
+        let prefix = url_prefix.trim_end_matches('/');
+        let entries = assets
+            .into_iter()
+            .map(|asset| {
+                let entry = Entry {
+                    url: format!(
+                        "{}/{}",
+                        prefix,
+                        asset.fingerprinted_name.unwrap_or(asset.name)
+                    ),
+                    content_type: asset.content_type.to_owned(),
+                    size: asset.content.len(),
+                    digest: Digest {
+                        hex: asset.digest.iter().fold(String::new(), |mut s, b| {
+                            write!(s, "{:02x}", b).expect("write to string");
+                            s
+                        }),
+                        base64: STANDARD.encode(asset.digest),
+                    },
+                    integrity: asset.integrity().map(str::to_owned),
+                    encodings: Encoding::ALL
+                        .into_iter()
+                        .filter_map(|enc| Some((enc.as_str(), asset.encoded(enc)?.len())))
+                        .collect(),
+                };
+                (asset.name.to_owned(), entry)
+            })
+            .collect();
+
+        Manifest { entries }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Entry> {
+        self.entries.get(name)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("serializing manifest")
+    }
+}
+
+impl<'a> Map<'a> {
+    /// Describes every asset in the map; see [`Manifest`].
+    pub fn manifest(&self, url_prefix: &str) -> Manifest {
+        Manifest::new(self.iter(), url_prefix)
+    }
+}
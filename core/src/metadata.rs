@@ -0,0 +1,17 @@
+//! How asset metadata is derived, shared by code generation and assets
+//! built at runtime, so that both agree.
+
+use blake2::{Blake2s256, Digest};
+
+/// The digest used for entity tags and fingerprinted names.
+pub fn digest(content: &[u8]) -> Vec<u8> {
+    Blake2s256::digest(content).to_vec()
+}
+
+/// Guesses a content type from the extension of a file name, falling back
+/// to `application/octet-stream`.
+pub fn content_type(name: &str) -> String {
+    mime_guess::from_path(name)
+        .first_or_octet_stream()
+        .to_string()
+}
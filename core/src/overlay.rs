@@ -0,0 +1,161 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::SystemTime;
+
+use crate::{metadata, AssetRef, AssetSource, OwnedAsset};
+
+// Keyed by asset name, and remembering the modification time and size that
+// the file was read with.
+type Cache = HashMap<String, (SystemTime, u64, Arc<OwnedAsset>)>;
+
+/// Serves files from a directory at runtime in preference to another
+/// source, so that operators can replace eg: a logo without rebuilding.
+///
+/// Content types and digests follow the same rules as code generation.
+/// Files are re-read when their modification time or size changes. Names
+/// with empty, `.` or `..` segments are never looked up on disk.
+///
+/// Overriding files are served under their logical name only; a
+/// fingerprinted name still finds the asset in the fallback source.
+#[derive(Debug)]
+pub struct Overlay<S> {
+    dir: PathBuf,
+    fallback: S,
+    cache: Mutex<Cache>,
+}
+
+impl<S: AssetSource> Overlay<S> {
+    pub fn new(dir: impl Into<PathBuf>, fallback: S) -> Self {
+        Overlay {
+            dir: dir.into(),
+            fallback,
+            cache: Mutex::default(),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Looks up an asset in the directory only.
+    pub fn get_file(&self, name: &str) -> Option<Arc<OwnedAsset>> {
+        let path = self.path(name)?;
+
+        // The cache is only locked between file system calls, so that
+        // lookups don't queue up behind each other's reads.
+        let (modified, len) = match fs::metadata(&path) {
+            Ok(metadata) if metadata.is_file() => (metadata.modified().ok()?, metadata.len()),
+            _ => {
+                self.cache().remove(name);
+                return None;
+            }
+        };
+        if let Some((cached_modified, cached_len, asset)) = self.cache().get(name) {
+            if *cached_modified == modified && *cached_len == len {
+                return Some(asset.clone());
+            }
+        }
+
+        let content = fs::read(&path).ok()?;
+        let asset = Arc::new(OwnedAsset {
+            name: name.to_owned(),
+            fingerprinted_name: None,
+            content_type: metadata::content_type(name),
+            digest: metadata::digest(&content),
+            content,
+            integrity: None,
+            gzip: None,
+            brotli: None,
+            zstd: None,
+        });
+        self.cache()
+            .insert(name.to_owned(), (modified, len, asset.clone()));
+        Some(asset)
+    }
+
+    fn cache(&self) -> MutexGuard<'_, Cache> {
+        self.cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn path(&self, name: &str) -> Option<PathBuf> {
+        let mut path = self.dir.clone();
+        for segment in name.split('/') {
+            if matches!(segment, "" | "." | "..") || segment.contains(['\\', ':']) {
+                return None;
+            }
+            path.push(segment);
+        }
+        Some(path)
+    }
+
+    // The names of every file beneath the directory, which is empty if it
+    // can't be read. Symlinks to files are followed, as `get_file` does,
+    // but symlinks to directories aren't, so that a loop can't trap us.
+    fn files(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut pending = vec![(self.dir.clone(), String::new())];
+        while let Some((dir, prefix)) = pending.pop() {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let Ok(file_name) = entry.file_name().into_string() else {
+                    continue;
+                };
+                let name = format!("{}{}", prefix, file_name);
+                let path = entry.path();
+                if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                    pending.push((path, format!("{}/", name)));
+                } else if fs::metadata(&path).is_ok_and(|metadata| metadata.is_file()) {
+                    names.push(name);
+                }
+            }
+        }
+        names.sort();
+        names
+    }
+}
+
+impl<S: AssetSource> AssetSource for Overlay<S> {
+    fn get(&self, name: &str) -> Option<AssetRef> {
+        self.get_file(name)
+            .map(AssetRef::Shared)
+            .or_else(|| self.fallback.get(name))
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = AssetRef> + '_> {
+        let files = self
+            .files()
+            .into_iter()
+            .filter_map(|name| self.get_file(&name))
+            .collect::<Vec<_>>();
+        let seen = files
+            .iter()
+            .map(|asset| asset.name.clone())
+            .collect::<HashSet<_>>();
+        let fallback = self
+            .fallback
+            .iter()
+            .filter(move |asset| !seen.contains(asset.asset().name));
+        Box::new(files.into_iter().map(AssetRef::Shared).chain(fallback))
+    }
+
+    fn names(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        let files = self
+            .files()
+            .into_iter()
+            .filter(|name| self.path(name).is_some())
+            .collect::<Vec<_>>();
+        let seen = files.iter().cloned().collect::<HashSet<_>>();
+        let fallback = self
+            .fallback
+            .names()
+            .filter(move |name| !seen.contains(name.as_ref()));
+        Box::new(files.into_iter().map(Cow::Owned).chain(fallback))
+    }
+}
@@ -0,0 +1,165 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{AssetRef, AssetSource, Map, OwnedAsset};
+
+/// A set of assets that owns its data, for assets built at runtime, eg: in
+/// tests or by plugins.
+#[derive(Debug, Clone, Default)]
+pub struct OwnedMap {
+    /// Sorted by logical name.
+    members: Vec<Arc<OwnedAsset>>,
+    /// Pairs of fingerprinted name and index into `members`, sorted by the
+    /// fingerprinted name.
+    fingerprinted: Vec<(String, usize)>,
+}
+
+/// Collects assets into an [`OwnedMap`].
+#[derive(Debug, Clone, Default)]
+pub struct MapBuilder {
+    assets: Vec<OwnedAsset>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// More than one asset would be served under the same name.
+    DuplicateName(String),
+}
+
+impl OwnedMap {
+    pub fn builder() -> MapBuilder {
+        MapBuilder::default()
+    }
+
+    /// Looks up an asset by any name it is served under.
+    pub fn get(&self, name: &str) -> Option<&Arc<OwnedAsset>> {
+        let idx = match self
+            .members
+            .binary_search_by(|asset| asset.name.as_str().cmp(name))
+        {
+            Ok(idx) => idx,
+            Err(_) => {
+                let idx = self
+                    .fingerprinted
+                    .binary_search_by(|(fingerprinted, _)| fingerprinted.as_str().cmp(name))
+                    .ok()?;
+                self.fingerprinted[idx].1
+            }
+        };
+        Some(&self.members[idx])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<OwnedAsset>> {
+        self.members.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+impl AssetSource for OwnedMap {
+    fn get(&self, name: &str) -> Option<AssetRef> {
+        OwnedMap::get(self, name).cloned().map(AssetRef::Shared)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = AssetRef> + '_> {
+        Box::new(self.members.iter().cloned().map(AssetRef::Shared))
+    }
+
+    fn names(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        let logical = self.members.iter().map(|asset| asset.name.as_str());
+        let fingerprinted = self.fingerprinted.iter().map(|(name, _)| name.as_str());
+        Box::new(logical.chain(fingerprinted).map(Cow::Borrowed))
+    }
+}
+
+/// Copies every asset, so that embedded assets can be combined with ones
+/// built at runtime. Assets are always served under their logical names,
+/// as well as any fingerprinted name, so this fails if one asset's
+/// fingerprinted name is another's logical name.
+impl TryFrom<&Map<'_>> for OwnedMap {
+    type Error = BuildError;
+
+    fn try_from(map: &Map<'_>) -> Result<Self, Self::Error> {
+        map.iter()
+            .fold(MapBuilder::default(), |builder, asset| {
+                builder.asset(OwnedAsset::from(&asset))
+            })
+            .build()
+    }
+}
+
+impl MapBuilder {
+    /// Adds an asset, deriving its content type from the name and its digest
+    /// from the content, as code generation does.
+    #[cfg(feature = "metadata")]
+    pub fn file(self, name: impl Into<String>, content: impl Into<Vec<u8>>) -> Self {
+        let name = name.into();
+        let content = content.into();
+        self.asset(OwnedAsset {
+            content_type: crate::metadata::content_type(&name),
+            digest: crate::metadata::digest(&content),
+            name,
+            fingerprinted_name: None,
+            content,
+            integrity: None,
+            gzip: None,
+            brotli: None,
+            zstd: None,
+        })
+    }
+
+    /// Adds an asset as given.
+    pub fn asset(mut self, asset: OwnedAsset) -> Self {
+        self.assets.push(asset);
+        self
+    }
+
+    /// Sorts the assets by name, as lookups expect.
+    pub fn build(mut self) -> Result<OwnedMap, BuildError> {
+        self.assets.sort_by(|a, b| a.name.cmp(&b.name));
+        let members = self.assets.into_iter().map(Arc::new).collect::<Vec<_>>();
+        if let Some(pair) = members.windows(2).find(|pair| pair[0].name == pair[1].name) {
+            return Err(BuildError::DuplicateName(pair[0].name.clone()));
+        }
+
+        let mut fingerprinted = members
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, asset)| Some((asset.fingerprinted_name.clone()?, idx)))
+            .collect::<Vec<_>>();
+        fingerprinted.sort();
+        for (name, _) in &fingerprinted {
+            if members
+                .binary_search_by(|asset| asset.name.cmp(name))
+                .is_ok()
+            {
+                return Err(BuildError::DuplicateName(name.clone()));
+            }
+        }
+        if let Some(pair) = fingerprinted.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(BuildError::DuplicateName(pair[0].0.clone()));
+        }
+
+        Ok(OwnedMap {
+            members,
+            fingerprinted,
+        })
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::DuplicateName(name) => write!(f, "Duplicate asset name: {}", name),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
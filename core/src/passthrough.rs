@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::SystemTime;
+
+use crate::{metadata, Asset, SourceDir};
+
+// Keyed by path, and remembering the modification time and size that the
+// asset was read with.
+type Cache = HashMap<PathBuf, (SystemTime, u64, &'static Asset<'static>)>;
+
+static CACHE: OnceLock<Mutex<Cache>> = OnceLock::new();
+
+/// Returns the current version of an embedded asset from disk, falling back
+/// to the embedded copy if the file can't be read.
+///
+/// Each version read is leaked, so that we can hand out references that
+/// live as long as the embedded assets do. This is only meant for use
+/// during development, where the number of edits is small.
+pub(crate) fn load<'a>(dir: SourceDir<'_>, embedded: &'a Asset<'a>) -> &'a Asset<'a> {
+    let Some(relative) = relative_path(dir.prefix, embedded.name) else {
+        return embedded;
+    };
+    let path = Path::new(dir.path).join(relative);
+    let (modified, len) = match std::fs::metadata(&path) {
+        Ok(metadata) => match metadata.modified() {
+            Ok(modified) => (modified, metadata.len()),
+            Err(_) => return embedded,
+        },
+        Err(_) => return embedded,
+    };
+
+    // The cache is only locked between file system calls, so that lookups
+    // don't queue up behind each other's reads.
+    if let Some(&(cached_modified, cached_len, asset)) = cache().get(&path) {
+        if cached_modified == modified && cached_len == len {
+            return asset;
+        }
+    }
+
+    let content = match std::fs::read(&path) {
+        Ok(content) => content,
+        Err(_) => return embedded,
+    };
+    let digest = metadata::digest(&content);
+
+    let mut cache = cache();
+    // Another lookup may have read the same version meanwhile, in which case
+    // we use theirs rather than leaking a second copy.
+    if let Some(&(cached_modified, cached_len, asset)) = cache.get(&path) {
+        if cached_modified == modified && cached_len == len {
+            return asset;
+        }
+    }
+    let asset: &'static Asset<'static> = Box::leak(Box::new(Asset {
+        name: leak_str(embedded.name),
+        fingerprinted_name: embedded.fingerprinted_name.map(leak_str),
+        content: content.leak(),
+        content_type: leak_str(embedded.content_type),
+        digest: digest.leak(),
+        integrity: None,
+        gzip: None,
+        brotli: None,
+        zstd: None,
+    }));
+    cache.insert(path, (modified, len, asset));
+    asset
+}
+
+fn cache() -> MutexGuard<'static, Cache> {
+    CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn leak_str(s: &str) -> &'static str {
+    s.to_owned().leak()
+}
+
+// Strips the prefix that names were generated with, to find the file's
+// path within the source directory.
+fn relative_path<'a>(prefix: &str, name: &'a str) -> Option<&'a str> {
+    let prefix = prefix.trim_matches('/');
+    if prefix.is_empty() {
+        return Some(name);
+    }
+    name.strip_prefix(prefix)?.strip_prefix('/')
+}
@@ -0,0 +1,151 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use crate::{live, Asset, Encoding, Map};
+
+/// A set of assets that can be looked up by name, whether embedded at
+/// compile time or loaded at runtime.
+pub trait AssetSource {
+    /// Looks up an asset by any name it is served under.
+    fn get(&self, name: &str) -> Option<AssetRef>;
+
+    /// Iterates over each asset once.
+    fn iter(&self) -> Box<dyn Iterator<Item = AssetRef> + '_>;
+
+    /// Iterates over every name that `get` will find an asset under.
+    fn names(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_>;
+}
+
+/// A handle to an asset that can outlive the lookup, so that responses can
+/// keep serving it even if the source changes.
+#[derive(Debug, Clone)]
+pub enum AssetRef {
+    Static(&'static Asset<'static>),
+    Shared(Arc<OwnedAsset>),
+}
+
+/// An asset that owns its data, for sources built or loaded at runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedAsset {
+    pub name: String,
+    pub fingerprinted_name: Option<String>,
+    pub content: Vec<u8>,
+    pub content_type: String,
+    pub digest: Vec<u8>,
+    pub integrity: Option<String>,
+    pub gzip: Option<Vec<u8>>,
+    pub brotli: Option<Vec<u8>>,
+    pub zstd: Option<Vec<u8>>,
+}
+
+impl AssetRef {
+    pub fn asset(&self) -> Asset<'_> {
+        match self {
+            AssetRef::Static(asset) => (*asset).clone(),
+            AssetRef::Shared(asset) => asset.as_asset(),
+        }
+    }
+}
+
+impl OwnedAsset {
+    pub fn as_asset(&self) -> Asset<'_> {
+        Asset {
+            name: &self.name,
+            fingerprinted_name: self.fingerprinted_name.as_deref(),
+            content: &self.content,
+            content_type: &self.content_type,
+            digest: &self.digest,
+            integrity: self.integrity.as_deref(),
+            gzip: self.gzip.as_deref(),
+            brotli: self.brotli.as_deref(),
+            zstd: self.zstd.as_deref(),
+        }
+    }
+
+    /// Returns the content in the given encoding, if a variant was stored.
+    pub fn encoded(&self, encoding: Encoding) -> Option<&[u8]> {
+        match encoding {
+            Encoding::Identity => Some(&self.content),
+            Encoding::Gzip => self.gzip.as_deref(),
+            Encoding::Brotli => self.brotli.as_deref(),
+            Encoding::Zstd => self.zstd.as_deref(),
+        }
+    }
+}
+
+impl From<&Asset<'_>> for OwnedAsset {
+    fn from(asset: &Asset<'_>) -> Self {
+        OwnedAsset {
+            name: asset.name.to_owned(),
+            fingerprinted_name: asset.fingerprinted_name.map(str::to_owned),
+            content: asset.content.to_vec(),
+            content_type: asset.content_type.to_owned(),
+            digest: asset.digest.to_vec(),
+            integrity: asset.integrity.map(str::to_owned),
+            gzip: asset.gzip.map(<[u8]>::to_vec),
+            brotli: asset.brotli.map(<[u8]>::to_vec),
+            zstd: asset.zstd.map(<[u8]>::to_vec),
+        }
+    }
+}
+
+impl AssetSource for Map<'static> {
+    fn get(&self, name: &str) -> Option<AssetRef> {
+        Map::get(self, name).map(AssetRef::Static)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = AssetRef> + '_> {
+        let source_dir = self.source_dir;
+        Box::new(
+            self.members
+                .iter()
+                .map(move |asset| AssetRef::Static(live(source_dir, asset))),
+        )
+    }
+
+    fn names(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        Box::new(self.routes().map(|(name, _)| Cow::Borrowed(name)))
+    }
+}
+
+impl<S: AssetSource + ?Sized> AssetSource for &S {
+    fn get(&self, name: &str) -> Option<AssetRef> {
+        (**self).get(name)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = AssetRef> + '_> {
+        (**self).iter()
+    }
+
+    fn names(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        (**self).names()
+    }
+}
+
+impl<S: AssetSource + ?Sized> AssetSource for Box<S> {
+    fn get(&self, name: &str) -> Option<AssetRef> {
+        (**self).get(name)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = AssetRef> + '_> {
+        (**self).iter()
+    }
+
+    fn names(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        (**self).names()
+    }
+}
+
+impl<S: AssetSource + ?Sized> AssetSource for Arc<S> {
+    fn get(&self, name: &str) -> Option<AssetRef> {
+        (**self).get(name)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = AssetRef> + '_> {
+        (**self).iter()
+    }
+
+    fn names(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        (**self).names()
+    }
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "metadata")]
+
+use static_assets::{AssetSource, Layered, OwnedAsset, OwnedMap};
+
+fn fingerprinted(name: &str, content: &str, fingerprinted_name: &str) -> OwnedAsset {
+    let map = OwnedMap::builder()
+        .file(name, content)
+        .build()
+        .expect("build");
+    OwnedAsset {
+        fingerprinted_name: Some(fingerprinted_name.into()),
+        ..(**map.get(name).expect(name)).clone()
+    }
+}
+
+fn layered() -> Layered {
+    let custom = OwnedMap::builder()
+        .file("logo.svg", "custom logo")
+        .file("custom.css", "custom css")
+        .build()
+        .expect("custom");
+    let base = OwnedMap::builder()
+        .file("logo.svg", "base logo")
+        .asset(fingerprinted("site.css", "base css", "site.0123abcd.css"))
+        .build()
+        .expect("base");
+    Layered::new().layer("custom", custom).layer("base", base)
+}
+
+#[test]
+fn should_prefer_earlier_layers() {
+    let assets = layered();
+
+    let (layer, logo) = assets.locate("logo.svg").expect("logo");
+    assert_eq!(layer, "custom");
+    assert_eq!(logo.asset().content, b"custom logo");
+
+    let (layer, site) = assets.locate("site.0123abcd.css").expect("site");
+    assert_eq!(layer, "base");
+    assert_eq!(site.asset().content, b"base css");
+
+    assert!(assets.get("missing.css").is_none());
+}
+
+#[test]
+fn should_iterate_without_shadowed_assets() {
+    let assets = layered();
+
+    let found = assets
+        .provenance()
+        .map(|(layer, asset)| (layer, asset.asset().name.to_owned()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        [
+            ("custom", "custom.css".to_owned()),
+            ("custom", "logo.svg".to_owned()),
+            ("base", "site.css".to_owned()),
+        ]
+    );
+    assert_eq!(AssetSource::iter(&assets).count(), 3);
+}
+
+#[test]
+fn should_list_each_name_once() {
+    let assets = layered();
+
+    let names = assets.names().collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        ["custom.css", "logo.svg", "site.css", "site.0123abcd.css"]
+    );
+    for name in names {
+        assert!(assets.get(&name).is_some(), "{}", name);
+    }
+}
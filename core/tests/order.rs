@@ -0,0 +1,88 @@
+use std::collections::BTreeSet;
+
+use proptest::prelude::*;
+use static_assets::{Asset, Map, OrderError, OwnedAsset, OwnedMap};
+
+// Separators and characters that sort either side of them, which is where
+// path and string ordering disagree.
+fn names() -> impl Strategy<Value = BTreeSet<String>> {
+    prop::collection::btree_set("[a-bA_ ./0-]{1,8}", 0..16)
+}
+
+fn asset(name: &str) -> Asset<'_> {
+    Asset {
+        name,
+        fingerprinted_name: None,
+        content: b"",
+        content_type: "text/plain",
+        digest: &[],
+        integrity: None,
+        gzip: None,
+        brotli: None,
+        zstd: None,
+    }
+}
+
+proptest! {
+    #[test]
+    fn should_find_every_member_of_sorted_map(names in names()) {
+        let members = names.iter().map(|name| asset(name)).collect::<Vec<_>>();
+        let map = Map::new(&members, &[], true, None);
+
+        for name in &names {
+            prop_assert_eq!(map.get(name).map(|a| a.name), Some(name.as_str()));
+        }
+    }
+
+    #[test]
+    fn should_only_accept_strictly_ascending_names(a in "[a-bA_ ./0-]{0,4}", b in "[a-bA_ ./0-]{0,4}") {
+        let members = [asset(&a), asset(&b)];
+        let map = Map {
+            members: &members,
+            fingerprinted: &[],
+            logical_names: true,
+            source_dir: None,
+        };
+
+        let expected = if a < b { Ok(()) } else { Err(OrderError::Members) };
+        prop_assert_eq!(map.check(), expected);
+    }
+
+    #[test]
+    fn should_find_every_member_of_owned_map(names in names(), seed in any::<u64>()) {
+        let mut shuffled = names.iter().cloned().collect::<Vec<_>>();
+        shuffled.sort_by_key(|name| (name.len() as u64).wrapping_mul(seed) ^ seed);
+
+        let map = shuffled
+            .iter()
+            .fold(OwnedMap::builder(), |builder, name| {
+                builder.asset(OwnedAsset::from(&asset(name)))
+            })
+            .build()
+            .expect("build");
+
+        for name in &names {
+            prop_assert_eq!(map.get(name).map(|a| a.name.as_str()), Some(name.as_str()));
+        }
+    }
+}
+
+#[test]
+fn should_reject_out_of_range_fingerprint_index() {
+    let members = [asset("a.css")];
+    let map = Map {
+        members: &members,
+        fingerprinted: &[("a.0123abcd.css", 1)],
+        logical_names: true,
+        source_dir: None,
+    };
+
+    assert_eq!(map.check(), Err(OrderError::Index));
+}
+
+#[test]
+#[should_panic(expected = "sorted by unique name")]
+fn should_panic_on_duplicate_members() {
+    let members = [asset("a.css"), asset("a.css")];
+    Map::new(&members, &[], true, None);
+}
@@ -0,0 +1,119 @@
+#![cfg(feature = "metadata")]
+
+use std::fs;
+
+use static_assets::{metadata, AssetRef, AssetSource, Overlay, OwnedMap};
+
+fn embedded() -> OwnedMap {
+    OwnedMap::builder()
+        .file("logo.svg", "<svg>embedded</svg>")
+        .file("site.css", "body {}")
+        .build()
+        .expect("build")
+}
+
+#[test]
+fn should_prefer_files_in_directory() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    fs::write(dir.path().join("logo.svg"), "<svg>custom</svg>").expect("write");
+    let assets = Overlay::new(dir.path(), embedded());
+
+    let logo = assets.get("logo.svg").expect("logo");
+    assert!(matches!(logo, AssetRef::Shared(_)));
+    let logo = logo.asset();
+    assert_eq!(logo.content, b"<svg>custom</svg>");
+    assert_eq!(logo.content_type, "image/svg+xml");
+    assert_eq!(logo.digest, metadata::digest(b"<svg>custom</svg>"));
+
+    let site = assets.get("site.css").expect("site");
+    assert_eq!(site.asset().content, b"body {}");
+}
+
+#[test]
+fn should_reload_changed_files_and_fall_back_when_removed() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let path = dir.path().join("site.css");
+    let assets = Overlay::new(dir.path(), embedded());
+
+    fs::write(&path, "body { color: red }").expect("write");
+    let first = assets.get_file("site.css").expect("first");
+    assert!(std::sync::Arc::ptr_eq(
+        &first,
+        &assets.get_file("site.css").expect("cached")
+    ));
+
+    fs::write(&path, "body { color: blue }").expect("write");
+    let second = assets.get_file("site.css").expect("second");
+    assert_eq!(second.content, b"body { color: blue }");
+    assert_ne!(first.digest, second.digest);
+
+    fs::remove_file(&path).expect("remove");
+    assert!(assets.get_file("site.css").is_none());
+    assert_eq!(
+        assets.get("site.css").expect("embedded").asset().content,
+        b"body {}"
+    );
+}
+
+#[test]
+fn should_not_escape_directory() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    fs::create_dir(dir.path().join("public")).expect("mkdir");
+    fs::write(dir.path().join("secret.txt"), "secret").expect("write");
+    let assets = Overlay::new(dir.path().join("public"), OwnedMap::default());
+
+    for name in [
+        "../secret.txt",
+        "./../secret.txt",
+        "/secret.txt",
+        "a//../../secret.txt",
+    ] {
+        assert!(assets.get(name).is_none(), "{}", name);
+    }
+}
+
+#[test]
+fn should_merge_names_with_fallback() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    fs::create_dir(dir.path().join("img")).expect("mkdir");
+    fs::write(dir.path().join("logo.svg"), "<svg>custom</svg>").expect("write");
+    fs::write(dir.path().join("img/extra.png"), "png").expect("write");
+    let assets = Overlay::new(dir.path(), embedded());
+
+    assert_eq!(
+        assets.names().collect::<Vec<_>>(),
+        ["img/extra.png", "logo.svg", "site.css"]
+    );
+    assert_eq!(
+        AssetSource::iter(&assets)
+            .map(|asset| asset.asset().content.to_vec())
+            .collect::<Vec<_>>(),
+        [&b"png"[..], b"<svg>custom</svg>", b"body {}"]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn should_only_list_names_that_can_be_served() {
+    use std::os::unix::fs::symlink;
+
+    let dir = tempfile::tempdir().expect("tempdir");
+    let outside = tempfile::tempdir().expect("tempdir");
+    fs::write(outside.path().join("shared.css"), "shared").expect("write");
+    fs::create_dir(outside.path().join("linked")).expect("mkdir");
+    symlink(
+        outside.path().join("shared.css"),
+        dir.path().join("shared.css"),
+    )
+    .expect("symlink");
+    symlink(outside.path().join("linked"), dir.path().join("linked")).expect("symlink");
+    symlink(dir.path().join("missing"), dir.path().join("dangling.css")).expect("symlink");
+    let assets = Overlay::new(dir.path(), OwnedMap::default());
+
+    let names = assets.names().collect::<Vec<_>>();
+    assert_eq!(names, ["shared.css"]);
+    for name in names {
+        assert!(assets.get(&name).is_some(), "{}", name);
+    }
+    assert_eq!(AssetSource::iter(&assets).count(), 1);
+}
@@ -0,0 +1,141 @@
+use static_assets::{AssetRef, AssetSource, BuildError, Map, MapBuilder, OwnedAsset, OwnedMap};
+
+fn asset(name: &str, fingerprinted_name: Option<&str>) -> OwnedAsset {
+    OwnedAsset {
+        name: name.into(),
+        fingerprinted_name: fingerprinted_name.map(Into::into),
+        content: name.as_bytes().to_vec(),
+        content_type: "text/plain".into(),
+        digest: vec![0; 32],
+        integrity: None,
+        gzip: None,
+        brotli: None,
+        zstd: None,
+    }
+}
+
+#[test]
+fn should_find_assets_regardless_of_insertion_order() {
+    let map = MapBuilder::default()
+        .asset(asset("z.txt", None))
+        .asset(asset("a.txt", Some("a.0123abcd.txt")))
+        .asset(asset("m/n.txt", None))
+        .build()
+        .expect("build");
+
+    assert_eq!(
+        map.iter().map(|a| a.name.as_str()).collect::<Vec<_>>(),
+        ["a.txt", "m/n.txt", "z.txt"]
+    );
+    for name in ["a.txt", "m/n.txt", "z.txt"] {
+        assert_eq!(map.get(name).expect(name).name, name);
+    }
+    assert_eq!(
+        map.get("a.0123abcd.txt").expect("fingerprinted").name,
+        "a.txt"
+    );
+    assert!(map.get("b.txt").is_none());
+}
+
+#[test]
+fn should_reject_duplicate_names() {
+    let err = OwnedMap::builder()
+        .asset(asset("a.txt", None))
+        .asset(asset("a.txt", None))
+        .build()
+        .expect_err("duplicate");
+    assert_eq!(err, BuildError::DuplicateName("a.txt".into()));
+
+    let err = OwnedMap::builder()
+        .asset(asset("a.txt", None))
+        .asset(asset("b.txt", Some("a.txt")))
+        .build()
+        .expect_err("duplicate");
+    assert_eq!(err, BuildError::DuplicateName("a.txt".into()));
+}
+
+#[test]
+fn should_serve_shared_assets_as_source() {
+    let map = OwnedMap::builder()
+        .asset(asset("a.txt", Some("a.0123abcd.txt")))
+        .build()
+        .expect("build");
+    let source: &dyn AssetSource = &map;
+
+    assert!(matches!(source.get("a.txt"), Some(AssetRef::Shared(_))));
+    assert_eq!(
+        source.names().collect::<Vec<_>>(),
+        ["a.txt", "a.0123abcd.txt"]
+    );
+}
+
+#[test]
+fn should_copy_embedded_map() {
+    static MAP: Map = Map {
+        members: &[static_assets::Asset {
+            name: "app.js",
+            fingerprinted_name: Some("app.0123abcd.js"),
+            content: b"console.log(\"Hi\")",
+            content_type: "text/javascript",
+            digest: &[0; 32],
+            integrity: None,
+            gzip: None,
+            brotli: None,
+            zstd: None,
+        }],
+        fingerprinted: &[("app.0123abcd.js", 0)],
+        logical_names: true,
+        source_dir: None,
+    };
+
+    let owned = OwnedMap::try_from(&MAP).expect("copy");
+
+    let asset = owned.get("app.0123abcd.js").expect("fingerprinted");
+    assert_eq!(asset.content, b"console.log(\"Hi\")");
+    assert_eq!(asset.content_type, "text/javascript");
+}
+
+#[cfg(feature = "metadata")]
+#[test]
+fn should_derive_metadata_as_codegen_does() {
+    let map = OwnedMap::builder()
+        .file("css/site.css", "body {}")
+        .file("data.bin", vec![0, 1, 2])
+        .build()
+        .expect("build");
+
+    let css = map.get("css/site.css").expect("css");
+    assert_eq!(css.content_type, "text/css");
+    assert_eq!(css.digest, static_assets::metadata::digest(b"body {}"));
+    assert_eq!(
+        map.get("data.bin").expect("bin").content_type,
+        "application/octet-stream"
+    );
+}
+
+#[test]
+fn should_reject_embedded_map_with_colliding_names() {
+    const A: static_assets::Asset = static_assets::Asset {
+        name: "a.js",
+        fingerprinted_name: Some("b.js"),
+        content: b"a",
+        content_type: "text/javascript",
+        digest: &[0; 32],
+        integrity: None,
+        gzip: None,
+        brotli: None,
+        zstd: None,
+    };
+    static MEMBERS: &[static_assets::Asset] = &[
+        A,
+        static_assets::Asset {
+            name: "b.js",
+            fingerprinted_name: None,
+            ..A
+        },
+    ];
+    static MAP: Map = Map::new(MEMBERS, &[("b.js", 0)], false, None);
+
+    let err = OwnedMap::try_from(&MAP).expect_err("collision");
+    assert_eq!(err, BuildError::DuplicateName("b.js".into()));
+}
@@ -0,0 +1,126 @@
+use globset::{Glob, GlobMatcher};
+use http::HeaderValue;
+use static_assets::Asset;
+
+use crate::Error;
+
+const DEFAULT_CACHE_CONTROL: &str = "no-cache";
+const DEFAULT_FINGERPRINTED_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// Chooses the `Cache-Control` header for each asset.
+///
+/// Requests for an asset by its fingerprinted name get a long-lived,
+/// immutable policy by default, as the name changes with the content.
+/// Otherwise, rules are checked in the order they were added, and the first
+/// that matches wins. Assets that match no rule get the default, which unless
+/// overridden is `no-cache`, ie: clients may store the asset, but must
+/// revalidate it with the entity tag before re-use.
+#[derive(Debug, Clone)]
+pub struct CachePolicy {
+    fingerprinted: Option<HeaderValue>,
+    rules: Vec<(Matcher, HeaderValue)>,
+    default: Option<HeaderValue>,
+}
+
+#[derive(Debug, Clone)]
+enum Matcher {
+    Glob(GlobMatcher),
+    ContentType(String),
+}
+
+impl CachePolicy {
+    /// Sets the value used for assets that match no rule.
+    pub fn with_default(mut self, cache_control: &str) -> Result<Self, Error> {
+        self.default = Some(HeaderValue::try_from(cache_control)?);
+        Ok(self)
+    }
+
+    /// Omits the `Cache-Control` header for assets that match no rule.
+    pub fn without_default(mut self) -> Self {
+        self.default = None;
+        self
+    }
+
+    /// Sets the value used when an asset is requested by its fingerprinted
+    /// name.
+    pub fn with_fingerprinted(mut self, cache_control: &str) -> Result<Self, Error> {
+        self.fingerprinted = Some(HeaderValue::try_from(cache_control)?);
+        Ok(self)
+    }
+
+    /// Applies the usual rules to requests for fingerprinted names.
+    pub fn without_fingerprinted(mut self) -> Self {
+        self.fingerprinted = None;
+        self
+    }
+
+    /// Adds a rule matching asset names (without a leading `/`) against a
+    /// glob pattern, eg: `*.html`. Note that `*` matches across `/`.
+    pub fn with_glob(mut self, pattern: &str, cache_control: &str) -> Result<Self, Error> {
+        let glob = Glob::new(pattern)?.compile_matcher();
+        self.rules
+            .push((Matcher::Glob(glob), HeaderValue::try_from(cache_control)?));
+        Ok(self)
+    }
+
+    /// Adds a rule matching an asset's content type. The pattern may be a
+    /// full type such as `text/css`, or a wildcard such as `image/*`.
+    pub fn with_content_type(
+        mut self,
+        content_type: &str,
+        cache_control: &str,
+    ) -> Result<Self, Error> {
+        self.rules.push((
+            Matcher::ContentType(content_type.to_ascii_lowercase()),
+            HeaderValue::try_from(cache_control)?,
+        ));
+        Ok(self)
+    }
+
+    pub fn cache_control(&self, asset: &Asset, fingerprinted: bool) -> Option<&HeaderValue> {
+        self.fingerprinted
+            .as_ref()
+            .filter(|_| fingerprinted)
+            .or_else(|| {
+                self.rules
+                    .iter()
+                    .find(|(matcher, _)| matcher.matches(asset))
+                    .map(|(_, value)| value)
+            })
+            .or(self.default.as_ref())
+    }
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        CachePolicy {
+            fingerprinted: Some(HeaderValue::from_static(
+                DEFAULT_FINGERPRINTED_CACHE_CONTROL,
+            )),
+            rules: Vec::new(),
+            default: Some(HeaderValue::from_static(DEFAULT_CACHE_CONTROL)),
+        }
+    }
+}
+
+impl Matcher {
+    fn matches(&self, asset: &Asset) -> bool {
+        match self {
+            Matcher::Glob(glob) => glob.is_match(asset.name),
+            Matcher::ContentType(pattern) => {
+                let essence = asset
+                    .content_type
+                    .split(';')
+                    .next()
+                    .unwrap_or_default()
+                    .trim();
+                match pattern.strip_suffix("/*") {
+                    Some(top_level) => essence
+                        .split_once('/')
+                        .is_some_and(|(ty, _)| ty.eq_ignore_ascii_case(top_level)),
+                    None => essence.eq_ignore_ascii_case(pattern),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The conditional request headers relevant to serving an asset, as per
+/// RFC 9110 section 13.
+///
+/// Entity-tag lists may be split over several header lines, so each of
+/// those holds every value that was sent.
+#[derive(Debug, Clone, Default)]
+pub struct Preconditions<'h> {
+    pub if_match: Vec<&'h str>,
+    pub if_none_match: Vec<&'h str>,
+    pub if_modified_since: Option<&'h str>,
+    pub if_unmodified_since: Option<&'h str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Carry on and serve the representation.
+    Proceed,
+    /// Respond with `304 Not Modified`.
+    NotModified,
+    /// Respond with `412 Precondition Failed`.
+    PreconditionFailed,
+}
+
+impl<'h> Preconditions<'h> {
+    /// Evaluates the preconditions in the order given by RFC 9110 section
+    /// 13.2.2, against the strong entity tag and (if known) modification
+    /// time of the selected representation.
+    ///
+    /// `safe_method` should be true for `GET` and `HEAD` requests; for other
+    /// methods a matching `If-None-Match` fails rather than producing a 304.
+    pub fn evaluate(
+        &self,
+        etag: &str,
+        last_modified: Option<SystemTime>,
+        safe_method: bool,
+    ) -> Outcome {
+        if !self.if_match.is_empty() {
+            if !matches_any(&self.if_match, etag, Comparison::Strong) {
+                return Outcome::PreconditionFailed;
+            }
+        } else if let Some(since) = parse_date(self.if_unmodified_since) {
+            if last_modified.is_some_and(|modified| truncate(modified) > since) {
+                return Outcome::PreconditionFailed;
+            }
+        }
+
+        if !self.if_none_match.is_empty() {
+            if matches_any(&self.if_none_match, etag, Comparison::Weak) {
+                return if safe_method {
+                    Outcome::NotModified
+                } else {
+                    Outcome::PreconditionFailed
+                };
+            }
+        } else if let (true, Some(since), Some(modified)) = (
+            safe_method,
+            parse_date(self.if_modified_since),
+            last_modified,
+        ) {
+            if truncate(modified) <= since {
+                return Outcome::NotModified;
+            }
+        }
+
+        Outcome::Proceed
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Strong,
+    Weak,
+}
+
+fn matches_any(values: &[&str], etag: &str, comparison: Comparison) -> bool {
+    let Some((false, ours)) = parse_entity_tag(etag) else {
+        return false;
+    };
+
+    values.iter().any(|val| {
+        EntityTags(val).any(|candidate| match candidate {
+            Candidate::Any => true,
+            Candidate::Tag { weak, opaque } => {
+                opaque == ours && (comparison == Comparison::Weak || !weak)
+            }
+        })
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Candidate<'a> {
+    Any,
+    Tag { weak: bool, opaque: &'a str },
+}
+
+// Iterates over the members of an entity-tag list, stopping at the first
+// malformed member. We can't just split on commas, as they are allowed
+// within the quoted part of a tag.
+struct EntityTags<'a>(&'a str);
+
+impl<'a> Iterator for EntityTags<'a> {
+    type Item = Candidate<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self
+            .0
+            .trim_start_matches(|c: char| c == ',' || c.is_ascii_whitespace());
+
+        if let Some(rest) = rest.strip_prefix('*') {
+            self.0 = rest;
+            return Some(Candidate::Any);
+        }
+
+        let (weak, rest) = match rest.strip_prefix("W/") {
+            Some(rest) => (true, rest),
+            None => (false, rest),
+        };
+        let rest = rest.strip_prefix('"')?;
+        let end = rest.find('"')?;
+        self.0 = &rest[end + 1..];
+
+        Some(Candidate::Tag {
+            weak,
+            opaque: &rest[..end],
+        })
+    }
+}
+
+fn parse_entity_tag(etag: &str) -> Option<(bool, &str)> {
+    match EntityTags(etag).next()? {
+        Candidate::Tag { weak, opaque } => Some((weak, opaque)),
+        Candidate::Any => None,
+    }
+}
+
+// Invalid dates are ignored, as RFC 9110 requires.
+fn parse_date(value: Option<&str>) -> Option<SystemTime> {
+    httpdate::parse_http_date(value?.trim()).ok()
+}
+
+// HTTP dates only have a resolution of one second.
+fn truncate(time: SystemTime) -> SystemTime {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(since_epoch) => UNIX_EPOCH + Duration::from_secs(since_epoch.as_secs()),
+        Err(_) => time,
+    }
+}
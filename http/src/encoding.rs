@@ -0,0 +1,102 @@
+use static_assets::{Asset, Encoding};
+
+// Quality values are stored in thousandths, as they have at most three
+// decimal places.
+const Q_MAX: u16 = 1000;
+const Q_MIN: u16 = 1;
+
+/// Picks the best stored encoding of `asset` given the values of any
+/// `Accept-Encoding` request headers.
+///
+/// The encoding with the highest quality value wins, and ties go to the
+/// smallest variant. When nothing stored is acceptable (eg: the client sent
+/// `identity;q=0` and there are no compressed variants), this falls back to
+/// `Identity`.
+pub fn negotiate_encoding<'h, I>(asset: &Asset, accept_encoding: I) -> Encoding
+where
+    I: IntoIterator<Item = &'h str>,
+{
+    let mut explicit: [Option<u16>; Encoding::ALL.len()] = [None; Encoding::ALL.len()];
+    let mut wildcard = None;
+    let mut seen_header = false;
+
+    for header in accept_encoding {
+        seen_header = true;
+        for (token, q) in header.split(',').filter_map(parse_coding) {
+            if token == "*" {
+                wildcard = Some(q);
+            } else if let Some(enc) = from_token(token) {
+                explicit[enc as usize] = Some(q);
+            }
+        }
+    }
+
+    if !seen_header {
+        return Encoding::Identity;
+    }
+
+    let quality = |enc: Encoding| match (explicit[enc as usize], wildcard, enc) {
+        (Some(q), _, _) => q,
+        (None, Some(q), _) => q,
+        // Identity is acceptable unless excluded, but we'd rather use
+        // anything the client asked for explicitly.
+        (None, None, Encoding::Identity) => Q_MIN,
+        (None, None, _) => 0,
+    };
+
+    asset
+        .encodings()
+        .map(|enc| (enc, quality(enc)))
+        .filter(|&(_, q)| q > 0)
+        .max_by(|&(a, qa), &(b, qb)| {
+            let size = |enc| asset.encoded(enc).map(<[u8]>::len).unwrap_or(usize::MAX);
+            qa.cmp(&qb).then_with(|| size(b).cmp(&size(a)))
+        })
+        .map(|(enc, _)| enc)
+        .unwrap_or(Encoding::Identity)
+}
+
+fn parse_coding(item: &str) -> Option<(&str, u16)> {
+    let mut parts = item.split(';').map(str::trim);
+    let token = parts.next().filter(|t| !t.is_empty())?;
+
+    let mut q = Q_MAX;
+    for param in parts {
+        let (name, value) = param.split_once('=')?;
+        if name.trim().eq_ignore_ascii_case("q") {
+            q = parse_qvalue(value.trim())?;
+        }
+    }
+
+    Some((token, q))
+}
+
+// qvalue = ( "0" [ "." 0*3DIGIT ] ) / ( "1" [ "." 0*3("0") ] )
+fn parse_qvalue(value: &str) -> Option<u16> {
+    let (int, frac) = value.split_once('.').unwrap_or((value, ""));
+    if frac.len() > 3 || !frac.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let frac = frac
+        .bytes()
+        .chain(std::iter::repeat(b'0'))
+        .take(3)
+        .fold(0u16, |acc, b| acc * 10 + u16::from(b - b'0'));
+
+    match (int, frac) {
+        ("0", frac) => Some(frac),
+        ("1", 0) => Some(Q_MAX),
+        _ => None,
+    }
+}
+
+fn from_token(token: &str) -> Option<Encoding> {
+    Encoding::ALL
+        .into_iter()
+        .find(|enc| token.eq_ignore_ascii_case(enc.as_str()))
+        .or_else(|| {
+            token
+                .eq_ignore_ascii_case("x-gzip")
+                .then_some(Encoding::Gzip)
+        })
+}
@@ -0,0 +1,100 @@
+use http::{header::ACCEPT, HeaderValue, Method, Request};
+
+use crate::Error;
+
+const DEFAULT_ASSET: &str = "index.html";
+const DEFAULT_CACHE_CONTROL: &str = "no-cache";
+
+/// Serves a page for unknown paths, so that a single-page application can
+/// route them on the client.
+///
+/// Only `GET` and `HEAD` requests that accept HTML fall back, and only when
+/// the last segment of the path has no extension, so that a missing
+/// `/app.0123abcd.js` is still a `404 Not Found`. The fallback is sent with
+/// `Cache-Control: no-cache` by default, as it stands in for many paths.
+#[derive(Debug, Clone)]
+pub struct Fallback {
+    asset: String,
+    excluded_prefixes: Vec<String>,
+    cache_control: HeaderValue,
+}
+
+impl Fallback {
+    /// Falls back to the named asset, eg: `index.html`.
+    pub fn new(asset: impl Into<String>) -> Self {
+        Fallback {
+            asset: asset.into(),
+            excluded_prefixes: Vec::new(),
+            cache_control: HeaderValue::from_static(DEFAULT_CACHE_CONTROL),
+        }
+    }
+
+    /// Never falls back for paths under the prefix, eg: `/api`.
+    pub fn with_excluded_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.excluded_prefixes.push(prefix.into());
+        self
+    }
+
+    /// Sets the `Cache-Control` header sent with the fallback.
+    pub fn with_cache_control(mut self, cache_control: &str) -> Result<Self, Error> {
+        self.cache_control = HeaderValue::try_from(cache_control)?;
+        Ok(self)
+    }
+
+    pub fn asset(&self) -> &str {
+        &self.asset
+    }
+
+    pub fn cache_control(&self) -> &HeaderValue {
+        &self.cache_control
+    }
+
+    /// Whether an unmatched request should get the fallback.
+    pub fn applies<B>(&self, req: &Request<B>) -> bool {
+        let path = req.uri().path();
+        let file = path.rsplit('/').next().unwrap_or_default();
+
+        (req.method() == Method::GET || req.method() == Method::HEAD)
+            && !file.contains('.')
+            && !self.is_excluded(path)
+            && accepts_html(req)
+    }
+
+    fn is_excluded(&self, path: &str) -> bool {
+        self.excluded_prefixes.iter().any(|prefix| {
+            let prefix = prefix.trim_end_matches('/');
+            path.strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+    }
+}
+
+impl Default for Fallback {
+    fn default() -> Self {
+        Fallback::new(DEFAULT_ASSET)
+    }
+}
+
+// Whether any `Accept` header admits `text/html`, ignoring ranges with a
+// quality of zero.
+fn accepts_html<B>(req: &Request<B>) -> bool {
+    req.headers()
+        .get_all(ACCEPT)
+        .iter()
+        .filter_map(|val| val.to_str().ok())
+        .flat_map(|val| val.split(','))
+        .any(|range| {
+            let mut params = range.split(';').map(str::trim);
+            let media_range = params.next().unwrap_or_default();
+            let rejected = params.any(|param| {
+                param
+                    .strip_prefix("q=")
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .is_some_and(|q| q == 0.0)
+            });
+            !rejected
+                && ["text/html", "text/*", "*/*"]
+                    .iter()
+                    .any(|html| media_range.eq_ignore_ascii_case(html))
+        })
+}
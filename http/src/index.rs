@@ -0,0 +1,67 @@
+use static_assets::{AssetRef, AssetSource};
+
+use crate::Config;
+
+/// What a request path refers to.
+#[derive(Debug, Clone)]
+pub enum Resolution {
+    Asset(AssetRef),
+    /// The path names a directory with an index file, but lacks the trailing
+    /// `/`; holds the location to redirect to, relative to the path.
+    Redirect(String),
+    NotFound,
+}
+
+/// Finds the asset for a request path. Paths ending with `/`, including
+/// the root, are served by the first of [`Config::index_files`] in that
+/// directory; `/docs` is redirected to `docs/` when `/docs/` would be.
+///
+/// The location is relative, as adapters may mount assets under a prefix
+/// that is stripped from the path before it gets here.
+pub fn resolve<S: AssetSource + ?Sized>(path: &str, assets: &S, config: &Config) -> Resolution {
+    let tail = path.strip_prefix('/').unwrap_or(path);
+
+    if tail.is_empty() || tail.ends_with('/') {
+        return match index(tail, assets, config) {
+            Some(asset) => Resolution::Asset(asset),
+            None => Resolution::NotFound,
+        };
+    }
+    if let Some(asset) = assets.get(tail) {
+        return Resolution::Asset(asset);
+    }
+
+    let dir = format!("{}/", tail);
+    if index(&dir, assets, config).is_none() {
+        return Resolution::NotFound;
+    }
+    let segment = tail.rsplit('/').next().unwrap_or(tail);
+    // A `:` in the first segment would make the location look like a URI
+    // with a scheme.
+    if segment.contains(':') {
+        Resolution::Redirect(format!("./{}/", segment))
+    } else {
+        Resolution::Redirect(format!("{}/", segment))
+    }
+}
+
+/// Returns the directory that an asset is the index of, eg: `docs/` for
+/// `docs/index.html`, or `` for `index.html`.
+pub fn index_dir<'a>(name: &'a str, config: &Config) -> Option<&'a str> {
+    let (dir, file) = match name.rfind('/') {
+        Some(idx) => name.split_at(idx + 1),
+        None => ("", name),
+    };
+    config
+        .index_files
+        .iter()
+        .any(|index| index == file)
+        .then_some(dir)
+}
+
+fn index<S: AssetSource + ?Sized>(dir: &str, assets: &S, config: &Config) -> Option<AssetRef> {
+    config
+        .index_files
+        .iter()
+        .find_map(|index| assets.get(&format!("{}{}", dir, index)))
+}
@@ -0,0 +1,327 @@
+use base64::{
+    alphabet::URL_SAFE,
+    engine::{general_purpose::NO_PAD, GeneralPurpose},
+    Engine,
+};
+use http::{
+    header::{
+        ACCEPT_ENCODING, ACCEPT_RANGES, CACHE_CONTROL, CONTENT_ENCODING, CONTENT_RANGE,
+        CONTENT_TYPE, ETAG, IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH, IF_RANGE,
+        IF_UNMODIFIED_SINCE, LOCATION, RANGE, VARY,
+    },
+    HeaderMap, HeaderName, HeaderValue, Method, Request, Response, StatusCode,
+};
+use std::ops::Range;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use static_assets::{Asset, AssetRef, AssetSource, Encoding, OwnedAsset};
+use thiserror::Error;
+use tracing::{debug, trace};
+
+use crate::conditional::{Outcome, Preconditions};
+use crate::range::RangeRequest;
+
+mod cache;
+pub mod conditional;
+mod encoding;
+mod fallback;
+mod index;
+pub mod range;
+
+pub use cache::CachePolicy;
+pub use encoding::negotiate_encoding;
+pub use fallback::Fallback;
+pub use index::{index_dir, resolve, Resolution};
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Invalid glob pattern")]
+    Glob(#[from] globset::Error),
+    #[error("Invalid header value")]
+    HeaderValue(#[from] http::header::InvalidHeaderValue),
+}
+
+/// Settings shared by every response.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub cache_policy: CachePolicy,
+    /// Names tried, in order, for paths ending with `/`; `index.html` and
+    /// `index.htm` by default.
+    pub index_files: Vec<String>,
+    /// The status used to add a trailing `/` to directory paths; `308
+    /// Permanent Redirect` by default.
+    pub redirect_status: StatusCode,
+    /// A page served for unknown paths, for single-page applications.
+    pub fallback: Option<Fallback>,
+}
+
+/// The content of a response, borrowing from the asset map where possible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Body<'a> {
+    Empty,
+    Borrowed(&'a [u8]),
+    Owned(Vec<u8>),
+    /// Shares the content of an asset loaded at runtime.
+    Shared(Bytes),
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            cache_policy: CachePolicy::default(),
+            index_files: vec!["index.html".into(), "index.htm".into()],
+            redirect_status: StatusCode::PERMANENT_REDIRECT,
+            fallback: None,
+        }
+    }
+}
+
+/// Serves the asset named by the request path (see [`resolve`]), the
+/// [`Config::fallback`] page, or a `404 Not Found`.
+pub fn serve<B, S: AssetSource + ?Sized>(
+    req: &Request<B>,
+    assets: &S,
+    config: &Config,
+) -> Result<Response<Body<'static>>, http::Error> {
+    try_serve(req, assets, config)
+        .or_else(|| try_fallback(req, assets, config))
+        .unwrap_or_else(|| {
+            debug!(path = req.uri().path(), "No match for path");
+            Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(Body::Empty)
+        })
+}
+
+/// Serves the asset named by the request path, or returns `None` when
+/// nothing matches, so that the request can be handled elsewhere. The
+/// fallback page is left to [`try_fallback`].
+pub fn try_serve<B, S: AssetSource + ?Sized>(
+    req: &Request<B>,
+    assets: &S,
+    config: &Config,
+) -> Option<Result<Response<Body<'static>>, http::Error>> {
+    let path = req.uri().path();
+    trace!(?path, "Path");
+
+    match resolve(path, assets, config) {
+        Resolution::Asset(asset) => Some(serve_ref(req, &asset, config)),
+        Resolution::Redirect(location) => Some(redirect(req, location, config)),
+        Resolution::NotFound => None,
+    }
+}
+
+/// Serves the [`Config::fallback`] page, if one is set and applies to the
+/// request.
+pub fn try_fallback<B, S: AssetSource + ?Sized>(
+    req: &Request<B>,
+    assets: &S,
+    config: &Config,
+) -> Option<Result<Response<Body<'static>>, http::Error>> {
+    let fallback = config.fallback.as_ref().filter(|f| f.applies(req))?;
+    let asset = assets.get(fallback.asset())?;
+    debug!(
+        path = req.uri().path(),
+        asset = fallback.asset(),
+        "Falling back"
+    );
+    Some(serve_fallback(req, &asset, fallback, config))
+}
+
+/// Serves a specific asset, regardless of the request path.
+pub fn serve_asset<'a, B>(
+    req: &Request<B>,
+    asset: &Asset<'a>,
+    config: &Config,
+) -> Result<Response<Body<'a>>, http::Error> {
+    respond(req, asset, config, |encoding, range| {
+        let content = asset
+            .encoded(encoding)
+            .expect("negotiated encoding should be available");
+        Body::Borrowed(&content[range])
+    })
+}
+
+/// Serves an asset from an [`AssetSource`], regardless of the request path.
+pub fn serve_ref<B>(
+    req: &Request<B>,
+    asset: &AssetRef,
+    config: &Config,
+) -> Result<Response<Body<'static>>, http::Error> {
+    match asset {
+        AssetRef::Static(asset) => serve_asset(req, asset, config),
+        AssetRef::Shared(asset) => respond(req, &asset.as_asset(), config, |encoding, range| {
+            Body::Shared(Bytes::from_owner(Encoded(asset.clone(), encoding)).slice(range))
+        }),
+    }
+}
+
+// The fallback stands in for many paths, and depends on whether the client
+// accepts HTML, so its own cache policy overrides the asset's.
+fn serve_fallback<B>(
+    req: &Request<B>,
+    asset: &AssetRef,
+    fallback: &Fallback,
+    config: &Config,
+) -> Result<Response<Body<'static>>, http::Error> {
+    let mut resp = serve_ref(req, asset, config)?;
+    let headers = resp.headers_mut();
+    headers.insert(CACHE_CONTROL, fallback.cache_control().clone());
+    headers.append(VARY, HeaderValue::from_static("accept"));
+    Ok(resp)
+}
+
+/// Redirects to the given path, keeping the query string.
+pub fn redirect<B>(
+    req: &Request<B>,
+    mut location: String,
+    config: &Config,
+) -> Result<Response<Body<'static>>, http::Error> {
+    if let Some(query) = req.uri().query() {
+        location.push('?');
+        location.push_str(query);
+    }
+    debug!(?location, "Redirecting");
+    Response::builder()
+        .status(config.redirect_status)
+        .header(LOCATION, location)
+        .body(Body::Empty)
+}
+
+// Lets a response body share an encoded variant of an owned asset.
+struct Encoded(Arc<OwnedAsset>, Encoding);
+
+impl AsRef<[u8]> for Encoded {
+    fn as_ref(&self) -> &[u8] {
+        self.0.encoded(self.1).unwrap_or_default()
+    }
+}
+
+// Builds the response for an asset, using `body` to refer to a range of
+// its content in the negotiated encoding.
+fn respond<'a, B>(
+    req: &Request<B>,
+    asset: &Asset<'_>,
+    config: &Config,
+    body: impl FnOnce(Encoding, Range<usize>) -> Body<'a>,
+) -> Result<Response<Body<'a>>, http::Error> {
+    let headers = req.headers();
+
+    let encoding = negotiate_encoding(asset, header_values(headers, ACCEPT_ENCODING));
+    trace!(name = asset.name, ?encoding, "Negotiated encoding");
+
+    let etag = encode_etag(asset, encoding);
+    let etag = etag.as_str();
+
+    let preconditions = Preconditions {
+        if_match: header_values(headers, IF_MATCH),
+        if_none_match: header_values(headers, IF_NONE_MATCH),
+        if_modified_since: headers
+            .get(IF_MODIFIED_SINCE)
+            .and_then(|val| val.to_str().ok()),
+        if_unmodified_since: headers
+            .get(IF_UNMODIFIED_SINCE)
+            .and_then(|val| val.to_str().ok()),
+    };
+    let safe_method = req.method() == Method::GET || req.method() == Method::HEAD;
+
+    let mut resp = Response::builder()
+        .header(ETAG, etag)
+        .header(VARY, ACCEPT_ENCODING.as_str());
+    let fingerprinted = is_fingerprinted_request(req.uri().path(), asset);
+    if let Some(cache_control) = config.cache_policy.cache_control(asset, fingerprinted) {
+        resp = resp.header(CACHE_CONTROL, cache_control);
+    }
+
+    match preconditions.evaluate(etag, None, safe_method) {
+        Outcome::Proceed => {}
+        Outcome::NotModified => {
+            return resp.status(StatusCode::NOT_MODIFIED).body(Body::Empty);
+        }
+        Outcome::PreconditionFailed => {
+            return Response::builder()
+                .status(StatusCode::PRECONDITION_FAILED)
+                .body(Body::Empty);
+        }
+    }
+
+    let content = asset
+        .encoded(encoding)
+        .expect("negotiated encoding should be available");
+
+    resp = resp.header(ACCEPT_RANGES, "bytes");
+    if encoding != Encoding::Identity {
+        resp = resp.header(CONTENT_ENCODING, encoding.as_str());
+    }
+
+    let if_range = headers
+        .get(IF_RANGE)
+        .map(|val| val.to_str().unwrap_or_default());
+    let range = if range::if_range_matches(if_range, etag) {
+        let header = headers.get(RANGE).and_then(|val| val.to_str().ok());
+        RangeRequest::parse(header, content.len())
+    } else {
+        RangeRequest::Full
+    };
+    trace!(name = asset.name, ?range, "Range request");
+
+    match range {
+        RangeRequest::Full => resp
+            .header(CONTENT_TYPE, asset.content_type)
+            .body(body(encoding, 0..content.len())),
+        RangeRequest::Partial(ranges) if ranges.len() == 1 => {
+            let range = ranges[0].clone();
+            resp.status(StatusCode::PARTIAL_CONTENT)
+                .header(CONTENT_TYPE, asset.content_type)
+                .header(CONTENT_RANGE, range::content_range(&range, content.len()))
+                .body(body(encoding, range))
+        }
+        RangeRequest::Partial(ranges) => {
+            let boundary = range::boundary(asset.digest);
+            let body = range::multipart_byteranges(content, asset.content_type, &ranges, &boundary);
+            resp.status(StatusCode::PARTIAL_CONTENT)
+                .header(CONTENT_TYPE, range::multipart_content_type(&boundary))
+                .body(Body::Owned(body))
+        }
+        RangeRequest::Unsatisfiable => resp
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(
+                CONTENT_RANGE,
+                range::unsatisfied_content_range(content.len()),
+            )
+            .body(Body::Empty),
+    }
+}
+
+// Adapters may mount assets under a prefix, so we check whether the path
+// ends with the fingerprinted name, rather than for equality.
+fn is_fingerprinted_request(path: &str, asset: &Asset) -> bool {
+    asset.fingerprinted_name.is_some_and(|name| {
+        path.strip_suffix(name)
+            .is_some_and(|prefix| prefix.is_empty() || prefix.ends_with('/'))
+    })
+}
+
+fn header_values(headers: &HeaderMap, name: HeaderName) -> Vec<&str> {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|val| val.to_str().ok())
+        .collect()
+}
+
+// Quotes, base64 encoded digest, and an optional `-<coding>` suffix. Digests
+// of runtime-built assets may be any length, so this is sized to fit.
+fn encode_etag(asset: &Asset, encoding: Encoding) -> String {
+    const BASE64_ENGINE: GeneralPurpose = GeneralPurpose::new(&URL_SAFE, NO_PAD);
+    let mut etag = String::with_capacity(asset.digest.len().div_ceil(3) * 4 + 16);
+    etag.push('"');
+    BASE64_ENGINE.encode_string(asset.digest, &mut etag);
+    if encoding != Encoding::Identity {
+        etag.push('-');
+        etag.push_str(encoding.as_str());
+    }
+    etag.push('"');
+    etag
+}
@@ -0,0 +1,151 @@
+use std::fmt::Write;
+use std::ops::Range;
+
+// Requests for more ranges than this are served in full, rather than doing
+// lots of work for a pathological (or malicious) client.
+const MAX_RANGES: usize = 32;
+
+/// The outcome of evaluating a `Range` request header against a
+/// representation of a given length.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeRequest {
+    /// The header was absent, malformed or used a unit other than `bytes`, so
+    /// the whole representation should be sent.
+    Full,
+    /// One or more satisfiable ranges, in the order they were requested.
+    Partial(Vec<Range<usize>>),
+    /// None of the requested ranges overlap the representation.
+    Unsatisfiable,
+}
+
+impl RangeRequest {
+    pub fn parse(header: Option<&str>, len: usize) -> Self {
+        let Some(header) = header else {
+            return RangeRequest::Full;
+        };
+        let Some(ranges) = parse_range_set(header, len) else {
+            return RangeRequest::Full;
+        };
+
+        if ranges.len() > MAX_RANGES {
+            RangeRequest::Full
+        } else if ranges.is_empty() {
+            RangeRequest::Unsatisfiable
+        } else {
+            RangeRequest::Partial(ranges)
+        }
+    }
+}
+
+// Returns `None` if the header is invalid and should be ignored, or the
+// satisfiable subset of the requested ranges.
+fn parse_range_set(header: &str, len: usize) -> Option<Vec<Range<usize>>> {
+    let (unit, set) = header.split_once('=')?;
+    if !unit.trim().eq_ignore_ascii_case("bytes") {
+        return None;
+    }
+
+    let mut ranges = Vec::new();
+    let mut specs = 0;
+    for spec in set.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        specs += 1;
+        let (first, last) = spec.split_once('-')?;
+        let (first, last) = (first.trim(), last.trim());
+
+        let range = if first.is_empty() {
+            let suffix = parse_digits(last)?;
+            (suffix > 0 && len > 0).then(|| len.saturating_sub(suffix)..len)
+        } else {
+            let first = parse_digits(first)?;
+            let last = if last.is_empty() {
+                None
+            } else {
+                Some(parse_digits(last)?)
+            };
+            if last.is_some_and(|last| last < first) {
+                return None;
+            }
+            let end = last.map_or(len, |last| last.saturating_add(1).min(len));
+            (first < len).then_some(first..end)
+        };
+
+        ranges.extend(range);
+    }
+
+    if specs == 0 {
+        return None;
+    }
+
+    Some(ranges)
+}
+
+fn parse_digits(s: &str) -> Option<usize> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    // Anything too large to index memory is effectively "the end".
+    Some(s.parse().unwrap_or(usize::MAX))
+}
+
+/// Formats a `Content-Range` value for a satisfied range.
+pub fn content_range(range: &Range<usize>, len: usize) -> String {
+    format!("bytes {}-{}/{}", range.start, range.end - 1, len)
+}
+
+/// Formats a `Content-Range` value for a `416 Range Not Satisfiable` response.
+pub fn unsatisfied_content_range(len: usize) -> String {
+    format!("bytes */{}", len)
+}
+
+/// Checks whether an `If-Range` header allows a range request to proceed.
+/// Only strong entity tags are compared, as we have no modification dates.
+pub fn if_range_matches(if_range: Option<&str>, etag: &str) -> bool {
+    match if_range.map(str::trim) {
+        None => true,
+        Some(val) => !val.starts_with("W/") && val == etag,
+    }
+}
+
+/// Builds a `multipart/byteranges` body with one part per range.
+pub fn multipart_byteranges(
+    content: &[u8],
+    content_type: &str,
+    ranges: &[Range<usize>],
+    boundary: &str,
+) -> Vec<u8> {
+    let mut body = Vec::new();
+    let mut headers = String::new();
+    for range in ranges {
+        headers.clear();
+        write!(
+            headers,
+            "--{}\r\nContent-Type: {}\r\nContent-Range: {}\r\n\r\n",
+            boundary,
+            content_type,
+            content_range(range, content.len())
+        )
+        .expect("write to string");
+        body.extend_from_slice(headers.as_bytes());
+        body.extend_from_slice(&content[range.clone()]);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+    body
+}
+
+/// The `Content-Type` for a `multipart/byteranges` body.
+pub fn multipart_content_type(boundary: &str) -> String {
+    format!("multipart/byteranges; boundary={}", boundary)
+}
+
+/// A multipart boundary derived from an asset's digest, so it is stable for
+/// a given asset, and vanishingly unlikely to occur within its content.
+pub fn boundary(digest: &[u8]) -> String {
+    digest
+        .iter()
+        .take(16)
+        .fold(String::from("static-assets-"), |mut s, b| {
+            write!(s, "{:02x}", b).expect("write to string");
+            s
+        })
+}
@@ -0,0 +1,114 @@
+use static_assets::Asset;
+use static_assets_http::CachePolicy;
+
+fn asset(name: &'static str, content_type: &'static str) -> Asset<'static> {
+    Asset {
+        name,
+        fingerprinted_name: None,
+        content: b"",
+        content_type,
+        digest: &[],
+        integrity: None,
+        gzip: None,
+        brotli: None,
+        zstd: None,
+    }
+}
+
+#[test]
+fn should_default_to_no_cache() {
+    let policy = CachePolicy::default();
+
+    let value = policy.cache_control(&asset("index.html", "text/html"), false);
+    assert_eq!(value.expect("Cache-Control"), "no-cache");
+}
+
+#[test]
+fn should_use_first_matching_rule() {
+    let policy = CachePolicy::default()
+        .with_glob("*.html", "no-cache")
+        .expect("html rule")
+        .with_glob("assets/**", "public, max-age=31536000, immutable")
+        .expect("assets rule")
+        .with_content_type("image/*", "public, max-age=86400")
+        .expect("image rule")
+        .with_default("public, max-age=60")
+        .expect("default");
+
+    let value = |name, content_type| {
+        policy
+            .cache_control(&asset(name, content_type), false)
+            .map(|v| v.to_str().expect("ascii").to_owned())
+    };
+
+    assert_eq!(
+        value("docs/index.html", "text/html").as_deref(),
+        Some("no-cache")
+    );
+    assert_eq!(
+        value("assets/app.3f9a1c2b.js", "application/javascript").as_deref(),
+        Some("public, max-age=31536000, immutable")
+    );
+    assert_eq!(
+        value("assets/logo.png", "image/png").as_deref(),
+        Some("public, max-age=31536000, immutable")
+    );
+    assert_eq!(
+        value("logo.png", "image/png").as_deref(),
+        Some("public, max-age=86400")
+    );
+    assert_eq!(
+        value("robots.txt", "text/plain").as_deref(),
+        Some("public, max-age=60")
+    );
+}
+
+#[test]
+fn should_match_exact_content_types_ignoring_parameters() {
+    let policy = CachePolicy::default()
+        .with_content_type("text/css", "max-age=10")
+        .expect("css rule")
+        .without_default();
+
+    assert_eq!(
+        policy
+            .cache_control(&asset("a.css", "text/css; charset=utf-8"), false)
+            .expect("Cache-Control"),
+        "max-age=10"
+    );
+    assert!(policy
+        .cache_control(&asset("a.html", "text/html"), false)
+        .is_none());
+}
+
+#[test]
+fn should_reject_invalid_rules() {
+    assert!(CachePolicy::default().with_glob("a[", "no-cache").is_err());
+    assert!(CachePolicy::default()
+        .with_glob("*.html", "no-cache\n")
+        .is_err());
+}
+
+#[test]
+fn should_cache_fingerprinted_requests_immutably() {
+    let policy = CachePolicy::default()
+        .with_glob("*.js", "no-store")
+        .expect("js rule");
+    let app = asset("app.js", "application/javascript");
+
+    assert_eq!(
+        policy.cache_control(&app, true).expect("Cache-Control"),
+        "public, max-age=31536000, immutable"
+    );
+    assert_eq!(
+        policy.cache_control(&app, false).expect("Cache-Control"),
+        "no-store"
+    );
+    assert_eq!(
+        policy
+            .without_fingerprinted()
+            .cache_control(&app, true)
+            .expect("Cache-Control"),
+        "no-store"
+    );
+}
@@ -0,0 +1,150 @@
+use std::time::{Duration, SystemTime};
+
+use static_assets_http::conditional::{Outcome, Preconditions};
+
+const ETAG: &str = "\"abc\"";
+
+#[test]
+fn should_proceed_without_preconditions() {
+    let pre = Preconditions::default();
+    assert_eq!(pre.evaluate(ETAG, None, true), Outcome::Proceed);
+}
+
+#[test]
+fn should_not_modify_on_matching_if_none_match() {
+    let pre = Preconditions {
+        if_none_match: vec![ETAG],
+        ..Default::default()
+    };
+    assert_eq!(pre.evaluate(ETAG, None, true), Outcome::NotModified);
+}
+
+#[test]
+fn should_match_if_none_match_weakly() {
+    let pre = Preconditions {
+        if_none_match: vec!["W/\"abc\""],
+        ..Default::default()
+    };
+    assert_eq!(pre.evaluate(ETAG, None, true), Outcome::NotModified);
+}
+
+#[test]
+fn should_match_if_none_match_within_lists() {
+    let pre = Preconditions {
+        if_none_match: vec!["\"x,y\", W/\"def\"", "\"ghi\" ,\"abc\""],
+        ..Default::default()
+    };
+    assert_eq!(pre.evaluate(ETAG, None, true), Outcome::NotModified);
+}
+
+#[test]
+fn should_proceed_on_mismatched_if_none_match() {
+    let pre = Preconditions {
+        if_none_match: vec!["\"def\", \"abcd\""],
+        ..Default::default()
+    };
+    assert_eq!(pre.evaluate(ETAG, None, true), Outcome::Proceed);
+}
+
+#[test]
+fn should_match_if_none_match_wildcard() {
+    let pre = Preconditions {
+        if_none_match: vec!["*"],
+        ..Default::default()
+    };
+    assert_eq!(pre.evaluate(ETAG, None, true), Outcome::NotModified);
+}
+
+#[test]
+fn should_fail_if_none_match_for_unsafe_methods() {
+    let pre = Preconditions {
+        if_none_match: vec![ETAG],
+        ..Default::default()
+    };
+    assert_eq!(pre.evaluate(ETAG, None, false), Outcome::PreconditionFailed);
+}
+
+#[test]
+fn should_fail_on_mismatched_if_match() {
+    let pre = Preconditions {
+        if_match: vec!["\"def\""],
+        ..Default::default()
+    };
+    assert_eq!(pre.evaluate(ETAG, None, true), Outcome::PreconditionFailed);
+}
+
+#[test]
+fn should_compare_if_match_strongly() {
+    let pre = Preconditions {
+        if_match: vec!["W/\"abc\""],
+        ..Default::default()
+    };
+    assert_eq!(pre.evaluate(ETAG, None, true), Outcome::PreconditionFailed);
+}
+
+#[test]
+fn should_proceed_on_matching_if_match() {
+    for if_match in ["\"abc\"", "\"def\", \"abc\"", "*"] {
+        let pre = Preconditions {
+            if_match: vec![if_match],
+            ..Default::default()
+        };
+        assert_eq!(
+            pre.evaluate(ETAG, None, true),
+            Outcome::Proceed,
+            "If-Match: {}",
+            if_match
+        );
+    }
+}
+
+#[test]
+fn should_not_modify_when_unchanged_since() {
+    let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_445_412_480);
+    let pre = Preconditions {
+        if_modified_since: Some("Wed, 21 Oct 2015 07:28:00 GMT"),
+        ..Default::default()
+    };
+    assert_eq!(
+        pre.evaluate(ETAG, Some(modified), true),
+        Outcome::NotModified
+    );
+    assert_eq!(
+        pre.evaluate(ETAG, Some(modified + Duration::from_secs(1)), true),
+        Outcome::Proceed
+    );
+}
+
+#[test]
+fn should_ignore_if_modified_since_with_if_none_match() {
+    let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_445_412_480);
+    let pre = Preconditions {
+        if_none_match: vec!["\"def\""],
+        if_modified_since: Some("Wed, 21 Oct 2015 07:28:00 GMT"),
+        ..Default::default()
+    };
+    assert_eq!(pre.evaluate(ETAG, Some(modified), true), Outcome::Proceed);
+}
+
+#[test]
+fn should_fail_when_modified_since_if_unmodified_since() {
+    let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_445_412_481);
+    let pre = Preconditions {
+        if_unmodified_since: Some("Wed, 21 Oct 2015 07:28:00 GMT"),
+        ..Default::default()
+    };
+    assert_eq!(
+        pre.evaluate(ETAG, Some(modified), true),
+        Outcome::PreconditionFailed
+    );
+}
+
+#[test]
+fn should_ignore_dates_without_modification_time() {
+    let pre = Preconditions {
+        if_modified_since: Some("Wed, 21 Oct 2015 07:28:00 GMT"),
+        if_unmodified_since: Some("Wed, 21 Oct 2015 07:28:00 GMT"),
+        ..Default::default()
+    };
+    assert_eq!(pre.evaluate(ETAG, None, true), Outcome::Proceed);
+}
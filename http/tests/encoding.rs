@@ -0,0 +1,84 @@
+use static_assets::{Asset, Encoding};
+use static_assets_http::negotiate_encoding;
+
+static ASSET: Asset = Asset {
+    name: "app.js",
+    fingerprinted_name: None,
+    content: b"0123456789abcdef",
+    content_type: "application/javascript",
+    digest: &[0; 32],
+    integrity: None,
+    gzip: Some(b"0123456789ab"),
+    brotli: Some(b"01234567"),
+    zstd: Some(b"0123456789"),
+};
+
+static UNCOMPRESSED: Asset = Asset {
+    name: "tiny.txt",
+    fingerprinted_name: None,
+    content: b"hi",
+    content_type: "text/plain",
+    digest: &[0; 32],
+    integrity: None,
+    gzip: None,
+    brotli: None,
+    zstd: None,
+};
+
+#[test]
+fn should_use_identity_without_header() {
+    assert_eq!(negotiate_encoding(&ASSET, []), Encoding::Identity);
+}
+
+#[test]
+fn should_prefer_smallest_of_equally_acceptable_encodings() {
+    assert_eq!(
+        negotiate_encoding(&ASSET, ["gzip, deflate, br, zstd"]),
+        Encoding::Brotli
+    );
+}
+
+#[test]
+fn should_respect_quality_values() {
+    assert_eq!(
+        negotiate_encoding(&ASSET, ["br;q=0.5, gzip;q=0.8, zstd;q=0.7"]),
+        Encoding::Gzip
+    );
+}
+
+#[test]
+fn should_not_use_encodings_with_zero_quality() {
+    assert_eq!(
+        negotiate_encoding(&ASSET, ["*, br;q=0, identity;q=0"]),
+        Encoding::Zstd
+    );
+}
+
+#[test]
+fn should_combine_multiple_header_values() {
+    assert_eq!(
+        negotiate_encoding(&ASSET, ["gzip;q=0.2", "zstd;q=0.4"]),
+        Encoding::Zstd
+    );
+}
+
+#[test]
+fn should_match_codings_case_insensitively() {
+    assert_eq!(negotiate_encoding(&ASSET, ["X-GZIP"]), Encoding::Gzip);
+}
+
+#[test]
+fn should_ignore_malformed_quality_values() {
+    assert_eq!(
+        negotiate_encoding(&ASSET, ["br;q=2, gzip;q=0.1"]),
+        Encoding::Gzip
+    );
+}
+
+#[test]
+fn should_fall_back_to_identity_when_nothing_acceptable() {
+    assert_eq!(
+        negotiate_encoding(&UNCOMPRESSED, ["gzip, identity;q=0"]),
+        Encoding::Identity
+    );
+}
@@ -0,0 +1,98 @@
+use http::{
+    header::{ACCEPT, CACHE_CONTROL, VARY},
+    Request, StatusCode,
+};
+use static_assets::OwnedMap;
+use static_assets_http::{serve, Body, Config, Fallback};
+
+const HTML: &str = "text/html,application/xhtml+xml,*/*;q=0.8";
+
+fn assets() -> OwnedMap {
+    OwnedMap::builder()
+        .file("index.html", "<div id=\"app\"></div>")
+        .file("app.0123abcd.js", "render()")
+        .build()
+        .expect("build")
+}
+
+fn config() -> Config {
+    Config {
+        fallback: Some(Fallback::default().with_excluded_prefix("/api")),
+        ..Config::default()
+    }
+}
+
+fn get(path: &str, accept: &str) -> http::Response<Body<'static>> {
+    let req = Request::get(path)
+        .header(ACCEPT, accept)
+        .body(())
+        .expect("request");
+    serve(&req, &assets(), &config()).expect("response")
+}
+
+#[test]
+fn should_serve_fallback_for_unknown_pages() {
+    for path in ["/settings", "/users/42/profile", "/users/"] {
+        let resp = get(path, HTML);
+
+        assert_eq!(resp.status(), StatusCode::OK, "{}", path);
+        assert_eq!(resp.headers()[CACHE_CONTROL], "no-cache");
+        assert!(resp.headers().get_all(VARY).iter().any(|v| v == "accept"));
+        match resp.into_body() {
+            Body::Shared(bytes) => assert_eq!(&bytes[..], b"<div id=\"app\"></div>"),
+            body => panic!("unexpected body {:?}", body),
+        }
+    }
+}
+
+#[test]
+fn should_not_fall_back_for_files() {
+    assert_eq!(get("/app.xyz.js", HTML).status(), StatusCode::NOT_FOUND);
+    assert_eq!(get("/app.0123abcd.js", HTML).status(), StatusCode::OK);
+}
+
+#[test]
+fn should_not_fall_back_for_excluded_prefixes() {
+    assert_eq!(get("/api", HTML).status(), StatusCode::NOT_FOUND);
+    assert_eq!(get("/api/users", HTML).status(), StatusCode::NOT_FOUND);
+    assert_eq!(get("/apiary", HTML).status(), StatusCode::OK);
+}
+
+#[test]
+fn should_only_fall_back_when_html_is_acceptable() {
+    assert_eq!(
+        get("/settings", "application/json").status(),
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        get("/settings", "text/html;q=0").status(),
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(get("/settings", "*/*").status(), StatusCode::OK);
+
+    let req = Request::post("/settings")
+        .header(ACCEPT, HTML)
+        .body(())
+        .expect("request");
+    let resp = serve(&req, &assets(), &config()).expect("response");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn should_use_configured_cache_control() {
+    let config = Config {
+        fallback: Some(
+            Fallback::new("index.html")
+                .with_cache_control("no-store")
+                .expect("cache control"),
+        ),
+        ..Config::default()
+    };
+    let req = Request::get("/settings")
+        .header(ACCEPT, HTML)
+        .body(())
+        .expect("request");
+    let resp = serve(&req, &assets(), &config).expect("response");
+
+    assert_eq!(resp.headers()[CACHE_CONTROL], "no-store");
+}
@@ -0,0 +1,97 @@
+use http::{header::LOCATION, Request, StatusCode};
+use static_assets::OwnedMap;
+use static_assets_http::{serve, Body, Config};
+
+fn assets() -> OwnedMap {
+    OwnedMap::builder()
+        .file("index.html", "<p>Home</p>")
+        .file("docs/index.html", "<p>Docs</p>")
+        .file("legacy/index.htm", "<p>Legacy</p>")
+        .file("docs/guide.html", "<p>Guide</p>")
+        .file("docs/api/index.html", "<p>API</p>")
+        .file("a:b/index.html", "<p>Colon</p>")
+        .build()
+        .expect("build")
+}
+
+fn get(path: &str, config: &Config) -> http::Response<Body<'static>> {
+    let req = Request::get(path).body(()).expect("request");
+    serve(&req, &assets(), config).expect("response")
+}
+
+fn content(resp: http::Response<Body<'static>>) -> Vec<u8> {
+    match resp.into_body() {
+        Body::Shared(bytes) => bytes.to_vec(),
+        body => panic!("unexpected body {:?}", body),
+    }
+}
+
+#[test]
+fn should_serve_index_files_for_directories() {
+    let config = Config::default();
+    for (path, expected) in [
+        ("/", "<p>Home</p>"),
+        ("/docs/", "<p>Docs</p>"),
+        ("/legacy/", "<p>Legacy</p>"),
+        ("/docs/index.html", "<p>Docs</p>"),
+    ] {
+        let resp = get(path, &config);
+        assert_eq!(resp.status(), StatusCode::OK, "{}", path);
+        assert_eq!(content(resp), expected.as_bytes(), "{}", path);
+    }
+}
+
+#[test]
+fn should_redirect_to_trailing_slash() {
+    let resp = get("/docs?page=2", &Config::default());
+
+    assert_eq!(resp.status(), StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(resp.headers()[LOCATION], "docs/?page=2");
+    assert_eq!(resp.into_body(), Body::Empty);
+}
+
+#[test]
+fn should_use_configured_redirect_status() {
+    let config = Config {
+        redirect_status: StatusCode::MOVED_PERMANENTLY,
+        ..Config::default()
+    };
+    let resp = get("/legacy", &config);
+
+    assert_eq!(resp.status(), StatusCode::MOVED_PERMANENTLY);
+    assert_eq!(resp.headers()[LOCATION], "legacy/");
+}
+
+#[test]
+fn should_only_try_configured_index_files() {
+    let config = Config {
+        index_files: vec!["index.html".into()],
+        ..Config::default()
+    };
+
+    assert_eq!(get("/legacy/", &config).status(), StatusCode::NOT_FOUND);
+    assert_eq!(get("/legacy", &config).status(), StatusCode::NOT_FOUND);
+    assert_eq!(
+        get("/docs/guide.html/", &config).status(),
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(get("/missing", &config).status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn should_not_redirect_to_other_hosts() {
+    let resp = get("//docs", &Config::default());
+
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+// Adapters may strip a prefix from the path before serving, so the location
+// is relative to the last segment.
+#[test]
+fn should_redirect_relative_to_request_path() {
+    let resp = get("/docs/api", &Config::default());
+    assert_eq!(resp.headers()[LOCATION], "api/");
+
+    let resp = get("/a:b", &Config::default());
+    assert_eq!(resp.headers()[LOCATION], "./a:b/");
+}
@@ -0,0 +1,26 @@
+use http::{header::ETAG, Request, StatusCode};
+use static_assets::{Overlay, OwnedMap};
+use static_assets_http::{serve, Body, Config};
+
+#[test]
+fn should_serve_overriding_file_with_its_own_etag() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let embedded = OwnedMap::builder()
+        .file("logo.svg", "<svg>embedded</svg>")
+        .build()
+        .expect("build");
+    let assets = Overlay::new(dir.path(), embedded);
+    let req = Request::get("/logo.svg").body(()).expect("request");
+
+    let before = serve(&req, &assets, &Config::default()).expect("response");
+    std::fs::write(dir.path().join("logo.svg"), "<svg>custom</svg>").expect("write");
+    let after = serve(&req, &assets, &Config::default()).expect("response");
+
+    assert_eq!(after.status(), StatusCode::OK);
+    assert_eq!(after.headers()["content-type"], "image/svg+xml");
+    assert_ne!(before.headers()[ETAG], after.headers()[ETAG]);
+    match after.body() {
+        Body::Shared(bytes) => assert_eq!(&bytes[..], b"<svg>custom</svg>"),
+        _ => panic!("expected shared body"),
+    }
+}
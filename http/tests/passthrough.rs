@@ -0,0 +1,82 @@
+use std::path::Path;
+
+use http::{header::ETAG, Request, StatusCode};
+use static_assets::{Asset, Map, SourceDir};
+use static_assets_http::{serve, Body, Config};
+
+static MEMBERS: &[Asset] = &[Asset {
+    name: "app.css",
+    fingerprinted_name: None,
+    content: b"body {}",
+    content_type: "text/css",
+    digest: &[0; 32],
+    integrity: None,
+    gzip: Some(b"compressed"),
+    brotli: None,
+    zstd: None,
+}];
+
+static PREFIXED_MEMBERS: &[Asset] = &[Asset {
+    name: "static/app.css",
+    ..MEMBERS[0]
+}];
+
+// Sources are expected to live for the whole program, as if embedded.
+fn map(dir: &Path) -> Map<'static> {
+    prefixed_map(dir, MEMBERS, "")
+}
+
+fn prefixed_map(dir: &Path, members: &'static [Asset], prefix: &'static str) -> Map<'static> {
+    let path = dir.to_str().expect("utf-8 path").to_owned();
+    Map {
+        members,
+        fingerprinted: &[],
+        logical_names: true,
+        source_dir: Some(SourceDir {
+            path: path.leak(),
+            prefix,
+        }),
+    }
+}
+
+#[test]
+fn should_serve_current_content_from_source_dir() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let assets = map(dir.path());
+    let req = Request::get("/app.css")
+        .header("accept-encoding", "gzip")
+        .body(())
+        .expect("request");
+
+    std::fs::write(dir.path().join("app.css"), "body { color: red }").expect("write");
+    let first = serve(&req, &assets, &Config::default()).expect("response");
+    assert_eq!(first.status(), StatusCode::OK);
+    assert_eq!(first.body(), &Body::Borrowed(b"body { color: red }"));
+
+    std::fs::write(dir.path().join("app.css"), "body { color: blue; }").expect("write");
+    let second = serve(&req, &assets, &Config::default()).expect("response");
+    assert_eq!(second.body(), &Body::Borrowed(b"body { color: blue; }"));
+    assert_ne!(first.headers().get(ETAG), second.headers().get(ETAG));
+}
+
+#[test]
+fn should_fall_back_to_embedded_content() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let assets = map(dir.path());
+
+    let req = Request::get("/app.css").body(()).expect("request");
+    let resp = serve(&req, &assets, &Config::default()).expect("response");
+    assert_eq!(resp.body(), &Body::Borrowed(b"body {}"));
+}
+
+#[test]
+fn should_read_prefixed_names_relative_to_source_dir() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let assets = prefixed_map(dir.path(), PREFIXED_MEMBERS, "static");
+    std::fs::write(dir.path().join("app.css"), "body { color: red }").expect("write");
+
+    let req = Request::get("/static/app.css").body(()).expect("request");
+    let resp = serve(&req, &assets, &Config::default()).expect("response");
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.body(), &Body::Borrowed(b"body { color: red }"));
+}
@@ -0,0 +1,119 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+use static_assets_http::range::{self, RangeRequest};
+
+#[test]
+fn should_serve_full_without_header() {
+    assert_eq!(RangeRequest::parse(None, 100), RangeRequest::Full);
+}
+
+#[test]
+fn should_parse_single_range() {
+    assert_eq!(
+        RangeRequest::parse(Some("bytes=0-9"), 100),
+        RangeRequest::Partial(vec![0..10])
+    );
+}
+
+#[test]
+fn should_clamp_range_to_length() {
+    assert_eq!(
+        RangeRequest::parse(Some("bytes=90-200"), 100),
+        RangeRequest::Partial(vec![90..100])
+    );
+}
+
+#[test]
+fn should_parse_open_ended_range() {
+    assert_eq!(
+        RangeRequest::parse(Some("bytes=95-"), 100),
+        RangeRequest::Partial(vec![95..100])
+    );
+}
+
+#[test]
+fn should_parse_suffix_range() {
+    assert_eq!(
+        RangeRequest::parse(Some("bytes=-5"), 100),
+        RangeRequest::Partial(vec![95..100])
+    );
+    assert_eq!(
+        RangeRequest::parse(Some("bytes=-500"), 100),
+        RangeRequest::Partial(vec![0..100])
+    );
+}
+
+#[test]
+fn should_parse_multiple_ranges() {
+    assert_eq!(
+        RangeRequest::parse(Some("bytes=0-1, 5-6 ,-2"), 100),
+        RangeRequest::Partial(vec![0..2, 5..7, 98..100])
+    );
+}
+
+#[test]
+fn should_skip_unsatisfiable_members_of_range_set() {
+    assert_eq!(
+        RangeRequest::parse(Some("bytes=200-300, 0-1"), 100),
+        RangeRequest::Partial(vec![0..2])
+    );
+}
+
+#[test]
+fn should_report_unsatisfiable_ranges() {
+    assert_eq!(
+        RangeRequest::parse(Some("bytes=100-"), 100),
+        RangeRequest::Unsatisfiable
+    );
+    assert_eq!(
+        RangeRequest::parse(Some("bytes=-0"), 100),
+        RangeRequest::Unsatisfiable
+    );
+    assert_eq!(
+        RangeRequest::parse(Some("bytes=0-"), 0),
+        RangeRequest::Unsatisfiable
+    );
+}
+
+#[test]
+fn should_ignore_invalid_headers() {
+    for header in [
+        "items=0-1",
+        "bytes=",
+        "bytes=5-1",
+        "bytes=a-b",
+        "bytes=0-1,x",
+        "bytes 0-1",
+    ] {
+        assert_eq!(
+            RangeRequest::parse(Some(header), 100),
+            RangeRequest::Full,
+            "Header: {:?}",
+            header
+        );
+    }
+}
+
+#[test]
+fn should_only_match_strong_if_range() {
+    assert!(range::if_range_matches(None, "\"abc\""));
+    assert!(range::if_range_matches(Some("\"abc\""), "\"abc\""));
+    assert!(!range::if_range_matches(Some("W/\"abc\""), "\"abc\""));
+    assert!(!range::if_range_matches(Some("\"def\""), "\"abc\""));
+    assert!(!range::if_range_matches(
+        Some("Wed, 21 Oct 2015 07:28:00 GMT"),
+        "\"abc\""
+    ));
+}
+
+#[test]
+fn should_build_multipart_body() {
+    let body = range::multipart_byteranges(b"0123456789", "text/plain", &[0..2, 8..10], "XYZ");
+
+    assert_eq!(
+        std::str::from_utf8(&body).expect("utf8"),
+        "--XYZ\r\nContent-Type: text/plain\r\nContent-Range: bytes 0-1/10\r\n\r\n01\r\n\
+         --XYZ\r\nContent-Type: text/plain\r\nContent-Range: bytes 8-9/10\r\n\r\n89\r\n\
+         --XYZ--\r\n"
+    );
+}
@@ -0,0 +1,144 @@
+use http::{
+    header::{ACCEPT_RANGES, CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH, VARY},
+    Method, Request, StatusCode,
+};
+use static_assets::Map;
+use static_assets_http::{serve, serve_asset, Body, CachePolicy, Config};
+use static_assets_macros::assets;
+
+static ASSETS: Map = assets!("../macros/tests/assets");
+
+#[test]
+fn should_serve_asset_content() {
+    let req = Request::get("/canary.html").body(()).expect("request");
+    let resp = serve(&req, &ASSETS, &Config::default()).expect("response");
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers().get(CONTENT_TYPE).expect("Content-Type"),
+        "text/html"
+    );
+    assert_eq!(
+        resp.headers().get(ACCEPT_RANGES).expect("Accept-Ranges"),
+        "bytes"
+    );
+    assert_eq!(resp.headers().get(VARY).expect("Vary"), "accept-encoding");
+    assert_eq!(resp.body(), &Body::Borrowed(b"<p>Hi!</p>\n"));
+}
+
+#[test]
+fn should_serve_404_when_missing() {
+    let req = Request::get("/not-an-asset").body(()).expect("request");
+    let resp = serve(&req, &ASSETS, &Config::default()).expect("response");
+
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert_eq!(resp.body(), &Body::Empty);
+}
+
+#[test]
+fn should_serve_asset_regardless_of_path() {
+    let asset = ASSETS.get("js/canary.js").expect("asset js/canary.js");
+    let req = Request::get("/elsewhere").body(()).expect("request");
+    let resp = serve_asset(&req, asset, &Config::default()).expect("response");
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.body(), &Body::Borrowed(asset.content));
+}
+
+#[test]
+fn should_fail_matching_if_none_match_for_unsafe_methods() {
+    let req = Request::get("/canary.html").body(()).expect("request");
+    let resp = serve(&req, &ASSETS, &Config::default()).expect("response");
+    let etag = resp.headers().get(ETAG).expect("ETag").clone();
+
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri("/canary.html")
+        .header(IF_NONE_MATCH, etag.clone())
+        .body(())
+        .expect("request");
+    let resp = serve(&req, &ASSETS, &Config::default()).expect("response");
+    assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(resp.headers().get(ETAG), Some(&etag));
+
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/canary.html")
+        .header(IF_NONE_MATCH, etag)
+        .body(())
+        .expect("request");
+    let resp = serve(&req, &ASSETS, &Config::default()).expect("response");
+    assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+}
+
+#[test]
+fn should_send_cache_control_with_not_modified() {
+    let config = Config {
+        cache_policy: CachePolicy::default()
+            .with_glob("*.html", "no-cache")
+            .expect("html rule")
+            .with_default("public, max-age=3600")
+            .expect("default"),
+
+        ..Config::default()
+    };
+
+    let req = Request::get("/js/canary.js").body(()).expect("request");
+    let resp = serve(&req, &ASSETS, &config).expect("response");
+    assert_eq!(
+        resp.headers().get(CACHE_CONTROL).expect("Cache-Control"),
+        "public, max-age=3600"
+    );
+
+    let req = Request::get("/canary.html").body(()).expect("request");
+    let resp = serve(&req, &ASSETS, &config).expect("response");
+    let etag = resp.headers().get(ETAG).expect("ETag").clone();
+
+    let req = Request::get("/canary.html")
+        .header(IF_NONE_MATCH, etag)
+        .body(())
+        .expect("request");
+    let resp = serve(&req, &ASSETS, &config).expect("response");
+    assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(
+        resp.headers().get(CACHE_CONTROL).expect("Cache-Control"),
+        "no-cache"
+    );
+}
+
+static FINGERPRINTED: Map = Map {
+    members: &[static_assets::Asset {
+        name: "app.js",
+        fingerprinted_name: Some("app.0123abcd.js"),
+        content: b"console.log(\"Hi\")",
+        content_type: "application/javascript",
+        digest: &[0; 32],
+        integrity: None,
+        gzip: None,
+        brotli: None,
+        zstd: None,
+    }],
+    fingerprinted: &[("app.0123abcd.js", 0)],
+    logical_names: true,
+    source_dir: None,
+};
+
+#[test]
+fn should_cache_fingerprinted_requests_immutably() {
+    let config = Config::default();
+
+    let req = Request::get("/app.0123abcd.js").body(()).expect("request");
+    let resp = serve(&req, &FINGERPRINTED, &config).expect("response");
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers().get(CACHE_CONTROL).expect("Cache-Control"),
+        "public, max-age=31536000, immutable"
+    );
+
+    let req = Request::get("/app.js").body(()).expect("request");
+    let resp = serve(&req, &FINGERPRINTED, &config).expect("response");
+    assert_eq!(
+        resp.headers().get(CACHE_CONTROL).expect("Cache-Control"),
+        "no-cache"
+    );
+}
@@ -0,0 +1,119 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use http::{
+    header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE, ETAG, IF_NONE_MATCH, RANGE},
+    Request, StatusCode,
+};
+use static_assets::{AssetRef, AssetSource, OwnedAsset, OwnedMap};
+use static_assets_http::{serve, Body, Config};
+
+// A minimal source of assets loaded at runtime.
+struct Runtime(BTreeMap<String, Arc<OwnedAsset>>);
+
+impl AssetSource for Runtime {
+    fn get(&self, name: &str) -> Option<AssetRef> {
+        self.0.get(name).cloned().map(AssetRef::Shared)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = AssetRef> + '_> {
+        Box::new(self.0.values().cloned().map(AssetRef::Shared))
+    }
+
+    fn names(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        Box::new(self.0.keys().map(|name| Cow::Borrowed(name.as_str())))
+    }
+}
+
+fn runtime() -> Runtime {
+    let asset = OwnedAsset {
+        name: "hello.txt".into(),
+        fingerprinted_name: None,
+        content: b"Hello, world".to_vec(),
+        content_type: "text/plain".into(),
+        digest: vec![0; 32],
+        integrity: None,
+        gzip: Some(b"pretend gzip".to_vec()),
+        brotli: None,
+        zstd: None,
+    };
+    Runtime(BTreeMap::from([(asset.name.clone(), Arc::new(asset))]))
+}
+
+#[test]
+fn should_serve_shared_content_from_runtime_source() {
+    let req = Request::get("/hello.txt").body(()).expect("request");
+    let resp = serve(&req, &runtime(), &Config::default()).expect("response");
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()[CONTENT_TYPE], "text/plain");
+    assert_eq!(resp.body(), &Body::Shared("Hello, world".into()));
+}
+
+#[test]
+fn should_serve_encoded_range_from_runtime_source() {
+    let req = Request::get("/hello.txt")
+        .header(ACCEPT_ENCODING, "gzip")
+        .header(RANGE, "bytes=8-11")
+        .body(())
+        .expect("request");
+    let resp = serve(&req, &runtime(), &Config::default()).expect("response");
+
+    assert_eq!(resp.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(resp.headers()[CONTENT_ENCODING], "gzip");
+    assert_eq!(resp.body(), &Body::Shared("gzip".into()));
+}
+
+#[test]
+fn should_not_find_missing_asset_in_runtime_source() {
+    let req = Request::get("/missing.txt").body(()).expect("request");
+    let resp = serve(&req, &runtime(), &Config::default()).expect("response");
+
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn should_list_names_of_runtime_source() {
+    let source: Box<dyn AssetSource> = Box::new(runtime());
+
+    assert_eq!(source.names().collect::<Vec<_>>(), ["hello.txt"]);
+    assert_eq!(source.iter().count(), 1);
+}
+
+// Digests of runtime-built assets are filled in by callers, so may be
+// longer than those generated at build time.
+#[test]
+fn should_serve_asset_with_long_digest() {
+    let assets = OwnedMap::builder()
+        .asset(OwnedAsset {
+            name: "hello.txt".into(),
+            fingerprinted_name: None,
+            content: b"Hello, world".to_vec(),
+            content_type: "text/plain".into(),
+            digest: vec![0xff; 48],
+            integrity: None,
+            gzip: Some(b"pretend gzip".to_vec()),
+            brotli: None,
+            zstd: None,
+        })
+        .build()
+        .expect("build");
+    let etag = format!("\"{}-gzip\"", "_".repeat(64));
+
+    let req = Request::get("/hello.txt")
+        .header(ACCEPT_ENCODING, "gzip")
+        .body(())
+        .expect("request");
+    let resp = serve(&req, &assets, &Config::default()).expect("response");
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()[ETAG], etag.as_str());
+
+    let req = Request::get("/hello.txt")
+        .header(ACCEPT_ENCODING, "gzip")
+        .header(IF_NONE_MATCH, etag.as_str())
+        .body(())
+        .expect("request");
+    let resp = serve(&req, &assets, &Config::default()).expect("response");
+    assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+}
@@ -0,0 +1,236 @@
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+
+use bytes::Bytes;
+use http_body_util::{Either, Full};
+use hyper::{Method, Request, Response, StatusCode};
+use static_assets::AssetSource;
+use static_assets_http::{CachePolicy, Config, Fallback};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::full_body;
+
+/// The body of a [`StaticFallthrough`] response: either an asset, or
+/// whatever the inner service responded with.
+pub type FallthroughBody<B> = Either<Full<Bytes>, B>;
+
+/// Wraps a service with [`StaticFallthrough`], eg: with
+/// `axum::Router::layer`, or `tower::ServiceBuilder`.
+#[derive(Clone)]
+pub struct StaticLayer {
+    assets: Arc<dyn AssetSource + Send + Sync>,
+    config: Arc<Config>,
+}
+
+/// Serves assets for `GET` and `HEAD` requests that match one, and passes
+/// everything else to the inner service unchanged.
+///
+/// The inner service is only driven to readiness for requests that are
+/// forwarded to it, using a clone, so that serving an asset never holds on
+/// to a slot reserved by eg: a concurrency limit.
+#[derive(Clone)]
+pub struct StaticFallthrough<S> {
+    inner: S,
+    assets: Arc<dyn AssetSource + Send + Sync>,
+    config: Arc<Config>,
+}
+
+/// The future returned by [`StaticFallthrough`].
+pub struct ResponseFuture<S, B, ResBody>
+where
+    S: Service<Request<B>>,
+{
+    state: State<S, B>,
+    fallback: Option<PendingFallback>,
+    _body: PhantomData<fn() -> ResBody>,
+}
+
+enum State<S, B>
+where
+    S: Service<Request<B>>,
+{
+    Served(Option<Response<Full<Bytes>>>),
+    // Waiting for a clone of the inner service to become ready.
+    Forwarding { inner: S, req: Option<Request<B>> },
+    Forwarded(Pin<Box<S::Future>>),
+}
+
+// What we need to serve the fallback page if the inner service responds
+// with `404 Not Found`, which is only built once it has.
+struct PendingFallback {
+    req: Request<()>,
+    assets: Arc<dyn AssetSource + Send + Sync>,
+    config: Arc<Config>,
+}
+
+impl StaticLayer {
+    pub fn new(assets: impl AssetSource + Send + Sync + 'static) -> Self {
+        Self::with_config(assets, Config::default())
+    }
+
+    pub fn with_config(assets: impl AssetSource + Send + Sync + 'static, config: Config) -> Self {
+        let assets = Arc::new(assets);
+        let config = Arc::new(config);
+        Self { assets, config }
+    }
+
+    pub fn with_cache_policy(mut self, cache_policy: CachePolicy) -> Self {
+        Arc::make_mut(&mut self.config).cache_policy = cache_policy;
+        self
+    }
+
+    /// Serves a page for paths that the inner service responds to with
+    /// `404 Not Found`; see [`Fallback`].
+    pub fn with_fallback(mut self, fallback: Fallback) -> Self {
+        Arc::make_mut(&mut self.config).fallback = Some(fallback);
+        self
+    }
+
+    /// Sets the names tried for paths ending with `/`; see
+    /// [`Config::index_files`].
+    pub fn with_index_files(mut self, names: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Arc::make_mut(&mut self.config).index_files = names.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+impl<S> Layer<S> for StaticLayer {
+    type Service = StaticFallthrough<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        StaticFallthrough {
+            inner,
+            assets: self.assets.clone(),
+            config: self.config.clone(),
+        }
+    }
+}
+
+impl<S> StaticFallthrough<S> {
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+}
+
+impl<S, B, ResBody> Service<Request<B>> for StaticFallthrough<S>
+where
+    S: Service<Request<B>, Response = Response<ResBody>> + Clone,
+{
+    type Response = Response<FallthroughBody<ResBody>>;
+
+    type Error = S::Error;
+
+    type Future = ResponseFuture<S, B, ResBody>;
+
+    // Readiness is checked when forwarding, as most requests may never
+    // reach the inner service.
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        let mut fallback = None;
+        if req.method() == Method::GET || req.method() == Method::HEAD {
+            if let Some(resp) = static_assets_http::try_serve(&req, &*self.assets, &self.config) {
+                return ResponseFuture {
+                    state: State::Served(Some(full_response(resp))),
+                    fallback: None,
+                    _body: PhantomData,
+                };
+            }
+            if self
+                .config
+                .fallback
+                .as_ref()
+                .is_some_and(|f| f.applies(&req))
+            {
+                fallback = Some(PendingFallback {
+                    req: request_head(&req),
+                    assets: self.assets.clone(),
+                    config: self.config.clone(),
+                });
+            }
+        }
+
+        ResponseFuture {
+            state: State::Forwarding {
+                inner: self.inner.clone(),
+                req: Some(req),
+            },
+            fallback,
+            _body: PhantomData,
+        }
+    }
+}
+
+// Neither the inner service nor the request are polled in place, and its
+// future is boxed, so nothing here needs to stay pinned.
+impl<S, B, ResBody> Unpin for ResponseFuture<S, B, ResBody> where S: Service<Request<B>> {}
+
+impl<S, B, ResBody> Future for ResponseFuture<S, B, ResBody>
+where
+    S: Service<Request<B>, Response = Response<ResBody>>,
+{
+    type Output = Result<Response<FallthroughBody<ResBody>>, S::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            match &mut this.state {
+                State::Served(resp) => {
+                    let resp = resp.take().expect("polled after completion");
+                    return Poll::Ready(Ok(resp.map(Either::Left)));
+                }
+                State::Forwarding { inner, req } => {
+                    ready!(inner.poll_ready(cx))?;
+                    let req = req.take().expect("polled after completion");
+                    this.state = State::Forwarded(Box::pin(inner.call(req)));
+                }
+                State::Forwarded(inner) => {
+                    let resp = ready!(inner.as_mut().poll(cx))?;
+                    if resp.status() == StatusCode::NOT_FOUND {
+                        if let Some(resp) = this.fallback.take().and_then(PendingFallback::serve) {
+                            return Poll::Ready(Ok(resp.map(Either::Left)));
+                        }
+                    }
+                    return Poll::Ready(Ok(resp.map(Either::Right)));
+                }
+            }
+        }
+    }
+}
+
+impl PendingFallback {
+    fn serve(self) -> Option<Response<Full<Bytes>>> {
+        static_assets_http::try_fallback(&self.req, &*self.assets, &self.config).map(full_response)
+    }
+}
+
+// Copies the parts of a request that responses depend on, leaving the body.
+fn request_head<B>(req: &Request<B>) -> Request<()> {
+    let mut head = Request::new(());
+    *head.method_mut() = req.method().clone();
+    *head.uri_mut() = req.uri().clone();
+    *head.version_mut() = req.version();
+    *head.headers_mut() = req.headers().clone();
+    head
+}
+
+// Responses are only built from valid parts, so this is not expected to
+// fail.
+fn full_response(
+    resp: Result<Response<static_assets_http::Body<'static>>, hyper::http::Error>,
+) -> Response<Full<Bytes>> {
+    match resp {
+        Ok(resp) => resp.map(full_body),
+        Err(_) => {
+            let mut resp = Response::new(Full::default());
+            *resp.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+            resp
+        }
+    }
+}
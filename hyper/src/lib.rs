@@ -1,32 +1,53 @@
-use base64::{
-    alphabet::URL_SAFE,
-    engine::{general_purpose::NO_PAD, GeneralPurpose},
-    Engine,
-};
+use std::sync::Arc;
+
 use bytes::Bytes;
 use futures::future;
 use http_body_util::Full;
-use hyper::{
-    header::{CONTENT_TYPE, ETAG},
-    http,
-    service::Service,
-    Request, Response, StatusCode,
-};
-use static_assets::Map;
-use tracing::{debug, trace};
+use hyper::{http, service::Service, Request, Response};
+use static_assets::AssetSource;
+use static_assets_http::Body;
 
-pub use static_assets_macros::assets;
+mod layer;
 
-const ETAG_STRING_SIZE: usize = 45;
+pub use layer::{FallthroughBody, ResponseFuture, StaticFallthrough, StaticLayer};
+pub use static_assets_http::{CachePolicy, Config, Fallback};
+pub use static_assets_macros::assets;
 
+/// Serves assets from any [`AssetSource`], such as a `&'static Map`, or an
+/// `Arc` of a source loaded at runtime.
 #[derive(Clone)]
 pub struct StaticService {
-    assets: &'static Map<'static>,
+    assets: Arc<dyn AssetSource + Send + Sync>,
+    config: Arc<Config>,
 }
 
 impl StaticService {
-    pub fn new(assets: &'static Map<'static>) -> Self {
-        Self { assets }
+    pub fn new(assets: impl AssetSource + Send + Sync + 'static) -> Self {
+        Self::with_config(assets, Config::default())
+    }
+
+    pub fn with_config(assets: impl AssetSource + Send + Sync + 'static, config: Config) -> Self {
+        let assets = Arc::new(assets);
+        let config = Arc::new(config);
+        Self { assets, config }
+    }
+
+    pub fn with_cache_policy(mut self, cache_policy: CachePolicy) -> Self {
+        Arc::make_mut(&mut self.config).cache_policy = cache_policy;
+        self
+    }
+
+    /// Serves a page for unknown paths; see [`Fallback`].
+    pub fn with_fallback(mut self, fallback: Fallback) -> Self {
+        Arc::make_mut(&mut self.config).fallback = Some(fallback);
+        self
+    }
+
+    /// Sets the names tried for paths ending with `/`; see
+    /// [`Config::index_files`].
+    pub fn with_index_files(mut self, names: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Arc::make_mut(&mut self.config).index_files = names.into_iter().map(Into::into).collect();
+        self
     }
 }
 
@@ -39,54 +60,17 @@ impl<B: hyper::body::Body> Service<Request<B>> for StaticService {
     type Future = future::Ready<Result<Self::Response, Self::Error>>;
 
     fn call(&self, req: Request<B>) -> Self::Future {
-        let path = req.uri().path();
-        let tail = path.strip_prefix('/').unwrap_or(path);
-        trace!(?path, ?tail, "Paths");
-        let asset = match self.assets.get(tail) {
-            Some(asset) => asset,
-            None => {
-                debug!(?path, "No match for path");
-                let resp = Response::builder()
-                    .status(StatusCode::NOT_FOUND)
-                    .body(Full::default());
-                return future::ready(resp);
-            }
-        };
-
-        let mut buf = [0u8; ETAG_STRING_SIZE];
-        let etag = encode_etag(&mut buf, asset);
-
-        let not_modified = req
-            .headers()
-            .get(http::header::IF_NONE_MATCH)
-            .and_then(|val| val.to_str().ok())
-            .map(|val| val == etag)
-            .unwrap_or(false);
-
-        if not_modified {
-            let resp = Response::builder()
-                .status(StatusCode::NOT_MODIFIED)
-                .body(Full::default());
-            return future::ready(resp);
-        }
-
-        let resp = Response::builder()
-            .header(CONTENT_TYPE, asset.content_type)
-            .header(ETAG, etag)
-            .body(Full::from(asset.content));
+        let resp = static_assets_http::serve(&req, &*self.assets, &self.config)
+            .map(|resp| resp.map(full_body));
         future::ready(resp)
     }
 }
 
-fn encode_etag<'a>(buf: &'a mut [u8; ETAG_STRING_SIZE], asset: &static_assets::Asset) -> &'a str {
-    const BASE64_ENGINE: GeneralPurpose = GeneralPurpose::new(&URL_SAFE, NO_PAD);
-    let mut off = 0;
-    buf[off] = b'"';
-    off += 1;
-    off += BASE64_ENGINE
-        .encode_slice(asset.digest, &mut buf[off..])
-        .unwrap();
-    buf[off] = b'"';
-    off += 1;
-    std::str::from_utf8(&buf[..off]).expect("Should only generate ASCII")
+fn full_body(body: Body<'static>) -> Full<Bytes> {
+    match body {
+        Body::Empty => Full::default(),
+        Body::Borrowed(content) => Full::from(content),
+        Body::Owned(content) => Full::from(content),
+        Body::Shared(content) => Full::from(content),
+    }
 }
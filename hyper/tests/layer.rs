@@ -0,0 +1,196 @@
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use http_body_util::{BodyExt, Empty, Full};
+use hyper::{header::ACCEPT, Method, Request, Response, StatusCode};
+use hyper_util::service::TowerToHyperService;
+use static_assets::{AssetRef, AssetSource, Map};
+use static_assets_hyper::{assets, Fallback, StaticLayer};
+use tower::{limit::ConcurrencyLimitLayer, service_fn, Layer, Service, ServiceExt};
+
+static ASSETS: Map = assets!("../macros/tests/assets");
+
+// Renders `/login`, has nothing under `/gone`, and is a teapot otherwise.
+async fn inner(req: Request<Empty<Bytes>>) -> Result<Response<Full<Bytes>>, Infallible> {
+    let path = req.uri().path();
+    let status = if path == "/login" {
+        StatusCode::OK
+    } else if path.starts_with("/gone/") {
+        StatusCode::NOT_FOUND
+    } else {
+        StatusCode::IM_A_TEAPOT
+    };
+    let body = format!("inner {} {}", req.method(), path);
+    Ok(Response::builder()
+        .status(status)
+        .body(Full::from(body))
+        .expect("response"))
+}
+
+// Counts lookups of the fallback page.
+struct CountingFallback(Arc<AtomicUsize>);
+
+impl AssetSource for CountingFallback {
+    fn get(&self, name: &str) -> Option<AssetRef> {
+        if name == "canary.html" {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+        AssetSource::get(&ASSETS, name)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = AssetRef> + '_> {
+        AssetSource::iter(&ASSETS)
+    }
+
+    fn names(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        ASSETS.names()
+    }
+}
+
+async fn fetch(layer: &StaticLayer, req: Request<Empty<Bytes>>) -> Result<(StatusCode, Bytes)> {
+    let srv = layer.layer(service_fn(inner));
+    let resp = srv.oneshot(req).await.context("Fetch response")?;
+    let status = resp.status();
+    let body = resp.into_body().collect().await.expect("body").to_bytes();
+    Ok((status, body))
+}
+
+#[tokio::test]
+async fn should_serve_matching_assets() -> Result<()> {
+    let layer = StaticLayer::new(&ASSETS);
+
+    let req = Request::get("/canary.html").body(Empty::new())?;
+    let (status, body) = fetch(&layer, req).await?;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "<p>Hi!</p>\n");
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_forward_misses_and_other_methods() -> Result<()> {
+    let layer = StaticLayer::new(&ASSETS);
+
+    let req = Request::get("/api/users").body(Empty::new())?;
+    assert_eq!(
+        fetch(&layer, req).await?,
+        (StatusCode::IM_A_TEAPOT, Bytes::from("inner GET /api/users"))
+    );
+
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/canary.html")
+        .body(Empty::new())?;
+    assert_eq!(
+        fetch(&layer, req).await?,
+        (
+            StatusCode::IM_A_TEAPOT,
+            Bytes::from("inner POST /canary.html")
+        )
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_only_fall_back_when_inner_service_has_nothing() -> Result<()> {
+    let layer = StaticLayer::new(&ASSETS)
+        .with_fallback(Fallback::new("canary.html").with_excluded_prefix("/api"));
+    let get = |path: &str| {
+        Request::get(path)
+            .header(ACCEPT, "text/html")
+            .body(Empty::new())
+    };
+
+    assert_eq!(
+        fetch(&layer, get("/login")?).await?,
+        (StatusCode::OK, Bytes::from("inner GET /login"))
+    );
+    assert_eq!(
+        fetch(&layer, get("/gone/page")?).await?,
+        (StatusCode::OK, Bytes::from("<p>Hi!</p>\n"))
+    );
+    assert_eq!(
+        fetch(&layer, get("/api/users")?).await?.0,
+        StatusCode::IM_A_TEAPOT
+    );
+
+    let req = Request::get("/gone/page").body(Empty::new())?;
+    assert_eq!(
+        fetch(&layer, req).await?,
+        (StatusCode::NOT_FOUND, Bytes::from("inner GET /gone/page"))
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_only_build_fallback_after_inner_service_responds() -> Result<()> {
+    let lookups = Arc::new(AtomicUsize::new(0));
+    let layer = StaticLayer::new(CountingFallback(lookups.clone()))
+        .with_fallback(Fallback::new("canary.html"));
+    let get = |path: &str| {
+        Request::get(path)
+            .header(ACCEPT, "text/html")
+            .body(Empty::new())
+    };
+
+    assert_eq!(fetch(&layer, get("/login")?).await?.0, StatusCode::OK);
+    assert_eq!(lookups.load(Ordering::SeqCst), 0);
+
+    assert_eq!(fetch(&layer, get("/gone/page")?).await?.0, StatusCode::OK);
+    assert_eq!(lookups.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_configured_index_files() -> Result<()> {
+    let layer = StaticLayer::new(&ASSETS).with_index_files(["canary.html"]);
+
+    let req = Request::get("/").body(Empty::new())?;
+    assert_eq!(
+        fetch(&layer, req).await?,
+        (StatusCode::OK, Bytes::from("<p>Hi!</p>\n"))
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_not_hold_inner_readiness_when_serving_assets() -> Result<()> {
+    let srv =
+        StaticLayer::new(&ASSETS).layer(ConcurrencyLimitLayer::new(1).layer(service_fn(inner)));
+
+    let mut serving = srv.clone();
+    let req = Request::get("/canary.html").body(Empty::new())?;
+    let resp = serving.ready().await?.call(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // `serving` is still alive, so would hold the only permit if it had
+    // readied the inner service.
+    let req = Request::get("/login").body(Empty::new())?;
+    let resp = tokio::time::timeout(Duration::from_secs(5), srv.oneshot(req))
+        .await
+        .context("forwarded request should not wait for a permit")??;
+    assert_eq!(resp.status(), StatusCode::OK);
+    drop(serving);
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_with_hyper() -> Result<()> {
+    use hyper::service::Service;
+
+    let srv = TowerToHyperService::new(StaticLayer::new(&ASSETS).layer(service_fn(inner)));
+
+    let req = Request::get("/css/canary.css").body(Empty::new())?;
+    let resp = srv.call(req).await.context("Fetch response")?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let req = Request::get("/missing").body(Empty::new())?;
+    let resp = srv.call(req).await.context("Fetch response")?;
+    assert_eq!(resp.status(), StatusCode::IM_A_TEAPOT);
+    Ok(())
+}
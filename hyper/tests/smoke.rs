@@ -3,13 +3,16 @@ use bytes::Bytes;
 use headers::{ContentType, HeaderMapExt};
 use http_body_util::{BodyExt, Empty};
 use hyper::{
-    header::{ETAG, IF_NONE_MATCH},
+    header::{
+        ACCEPT, ACCEPT_ENCODING, ACCEPT_RANGES, CACHE_CONTROL, CONTENT_ENCODING, CONTENT_RANGE,
+        CONTENT_TYPE, ETAG, IF_MATCH, IF_NONE_MATCH, IF_RANGE, LOCATION, RANGE, VARY,
+    },
     service::HttpService,
     Request, StatusCode,
 };
-use static_assets::Map;
+use static_assets::{Layered, Map, OwnedMap};
 
-use static_assets_hyper::{assets, StaticService};
+use static_assets_hyper::{assets, CachePolicy, Fallback, StaticService};
 
 static ASSETS: Map = assets!("../macros/tests/assets");
 
@@ -131,3 +134,343 @@ async fn should_serve_content_with_etag_from_different_resource() -> Result<()>
 
     Ok(())
 }
+
+#[tokio::test]
+async fn should_serve_negotiated_encoding() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let srv = StaticService::new(&ASSETS);
+    let req = Request::builder()
+        .uri("/css/canary.css")
+        .header(ACCEPT_ENCODING, "gzip;q=1.0, identity;q=0.5")
+        .body(Empty::<Bytes>::new())?;
+    let resp = srv.clone().call(req).await.context("Fetch response")?;
+    let (parts, body) = resp.into_parts();
+
+    assert_eq!(parts.status, StatusCode::OK);
+    assert_eq!(
+        parts
+            .headers
+            .get(CONTENT_ENCODING)
+            .expect("Content-Encoding"),
+        "gzip"
+    );
+    assert_eq!(parts.headers.get(VARY).expect("Vary"), "accept-encoding");
+
+    let body = body.collect().await.expect("collecting body").to_bytes();
+    let asset = ASSETS.get("css/canary.css").expect("asset");
+    assert_eq!(&body[..], asset.gzip.expect("gzip variant"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_identity_when_encodings_refused() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let srv = StaticService::new(&ASSETS);
+    let req = Request::builder()
+        .uri("/css/canary.css")
+        .header(ACCEPT_ENCODING, "gzip;q=0, br;q=0")
+        .body(Empty::<Bytes>::new())?;
+    let resp = srv.clone().call(req).await.context("Fetch response")?;
+    let (parts, body) = resp.into_parts();
+
+    assert_eq!(parts.status, StatusCode::OK);
+    assert!(parts.headers.get(CONTENT_ENCODING).is_none());
+
+    let body = body.collect().await.expect("collecting body").to_bytes();
+    let asset = ASSETS.get("css/canary.css").expect("asset");
+    assert_eq!(&body[..], asset.content);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_single_range() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let srv = StaticService::new(&ASSETS);
+    let req = Request::builder()
+        .uri("/canary.html")
+        .header(RANGE, "bytes=3-5")
+        .body(Empty::<Bytes>::new())?;
+    let resp = srv.clone().call(req).await.context("Fetch response")?;
+    let (parts, body) = resp.into_parts();
+
+    assert_eq!(parts.status, StatusCode::PARTIAL_CONTENT);
+    assert_eq!(
+        parts.headers.get(ACCEPT_RANGES).expect("Accept-Ranges"),
+        "bytes"
+    );
+    assert_eq!(
+        parts.headers.get(CONTENT_RANGE).expect("Content-Range"),
+        "bytes 3-5/11"
+    );
+    let body = body.collect().await.expect("collecting body").to_bytes();
+    assert_eq!(&body[..], b"Hi!");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_multiple_ranges_as_multipart() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let srv = StaticService::new(&ASSETS);
+    let req = Request::builder()
+        .uri("/canary.html")
+        .header(RANGE, "bytes=0-2,-5")
+        .body(Empty::<Bytes>::new())?;
+    let resp = srv.clone().call(req).await.context("Fetch response")?;
+    let (parts, body) = resp.into_parts();
+
+    assert_eq!(parts.status, StatusCode::PARTIAL_CONTENT);
+    let content_type = parts
+        .headers
+        .get(CONTENT_TYPE)
+        .expect("Content-Type")
+        .to_str()?;
+    let boundary = content_type
+        .strip_prefix("multipart/byteranges; boundary=")
+        .expect("multipart content type");
+
+    let body = body.collect().await.expect("collecting body").to_bytes();
+    let bodystr = std::str::from_utf8(&body).context("utf8 body")?;
+    assert_eq!(
+        bodystr,
+        format!(
+            "--{b}\r\nContent-Type: text/html\r\nContent-Range: bytes 0-2/11\r\n\r\n<p>\r\n\
+             --{b}\r\nContent-Type: text/html\r\nContent-Range: bytes 6-10/11\r\n\r\n</p>\n\r\n\
+             --{b}--\r\n",
+            b = boundary
+        )
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_reject_unsatisfiable_range() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let srv = StaticService::new(&ASSETS);
+    let req = Request::builder()
+        .uri("/canary.html")
+        .header(RANGE, "bytes=100-200")
+        .body(Empty::<Bytes>::new())?;
+    let resp = srv.clone().call(req).await.context("Fetch response")?;
+    let (parts, _) = resp.into_parts();
+
+    assert_eq!(parts.status, StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(
+        parts.headers.get(CONTENT_RANGE).expect("Content-Range"),
+        "bytes */11"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_full_content_when_if_range_does_not_match() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let srv = StaticService::new(&ASSETS);
+    let req = Request::builder()
+        .uri("/canary.html")
+        .header(RANGE, "bytes=3-5")
+        .header(IF_RANGE, "\"stale\"")
+        .body(Empty::<Bytes>::new())?;
+    let resp = srv.clone().call(req).await.context("Fetch response")?;
+    let (parts, body) = resp.into_parts();
+
+    assert_eq!(parts.status, StatusCode::OK);
+    let body = body.collect().await.expect("collecting body").to_bytes();
+    assert_eq!(&body[..], b"<p>Hi!</p>\n");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_not_modified_for_weak_tag_in_list() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let srv = StaticService::new(&ASSETS);
+    let req = Request::builder()
+        .uri("/canary.html")
+        .body(Empty::<Bytes>::new())?;
+    let resp = srv.clone().call(req).await.context("Fetch response")?;
+    let (parts, _) = resp.into_parts();
+    let entity_tag = parts
+        .headers
+        .get(ETAG)
+        .expect("some ETag header")
+        .to_str()?;
+
+    let req = Request::builder()
+        .uri("/canary.html")
+        .header(IF_NONE_MATCH, format!("\"other\", W/{}", entity_tag))
+        .body(Empty::<Bytes>::new())?;
+    let resp = srv.clone().call(req).await.context("Fetch response")?;
+    let (parts, _) = resp.into_parts();
+
+    assert_eq!(parts.status, StatusCode::NOT_MODIFIED);
+    assert_eq!(parts.headers.get(ETAG).expect("ETag on 304"), entity_tag);
+    assert!(parts.headers.get(VARY).is_some(), "Vary on 304");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_fail_precondition_on_if_match_mismatch() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let srv = StaticService::new(&ASSETS);
+    let req = Request::builder()
+        .uri("/canary.html")
+        .header(IF_MATCH, "\"other\"")
+        .body(Empty::<Bytes>::new())?;
+    let resp = srv.clone().call(req).await.context("Fetch response")?;
+    let (parts, _) = resp.into_parts();
+
+    assert_eq!(parts.status, StatusCode::PRECONDITION_FAILED);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_configured_cache_control() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let policy = CachePolicy::default().with_glob("js/*", "public, max-age=31536000, immutable")?;
+    let srv = StaticService::new(&ASSETS).with_cache_policy(policy);
+
+    let req = Request::builder()
+        .uri("/js/canary.js")
+        .body(Empty::<Bytes>::new())?;
+    let resp = srv.clone().call(req).await.context("Fetch response")?;
+    assert_eq!(
+        resp.headers().get(CACHE_CONTROL).expect("Cache-Control"),
+        "public, max-age=31536000, immutable"
+    );
+
+    let req = Request::builder()
+        .uri("/canary.html")
+        .body(Empty::<Bytes>::new())?;
+    let resp = srv.clone().call(req).await.context("Fetch response")?;
+    assert_eq!(
+        resp.headers().get(CACHE_CONTROL).expect("Cache-Control"),
+        "no-cache"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_owned_map() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let assets = OwnedMap::builder()
+        .file("hello.txt", "Hello, world")
+        .build()?;
+    let srv = StaticService::new(assets);
+
+    let req = Request::builder()
+        .uri("/hello.txt")
+        .body(Empty::<Bytes>::new())?;
+    let resp = srv.clone().call(req).await.context("Fetch response")?;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()[CONTENT_TYPE], "text/plain");
+    let body = resp.into_body().collect().await?.to_bytes();
+    assert_eq!(body, "Hello, world");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_layered_overrides() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let overrides = OwnedMap::builder()
+        .file("canary.html", "<p>Overridden</p>")
+        .build()?;
+    let srv = StaticService::new(
+        Layered::new()
+            .layer("overrides", overrides)
+            .layer("embedded", &ASSETS),
+    );
+
+    let req = Request::builder()
+        .uri("/canary.html")
+        .body(Empty::<Bytes>::new())?;
+    let resp = srv.clone().call(req).await.context("Fetch response")?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = resp.into_body().collect().await?.to_bytes();
+    assert_eq!(body, "<p>Overridden</p>");
+
+    let req = Request::builder()
+        .uri("/css/canary.css")
+        .body(Empty::<Bytes>::new())?;
+    let resp = srv.clone().call(req).await.context("Fetch response")?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_directory_index() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let assets = OwnedMap::builder()
+        .file("docs/index.html", "<p>Docs</p>")
+        .file("docs/default.html", "<p>Default</p>")
+        .build()?;
+    let srv = StaticService::new(assets).with_index_files(["default.html"]);
+
+    let req = Request::builder()
+        .uri("/docs/")
+        .body(Empty::<Bytes>::new())?;
+    let resp = srv.clone().call(req).await.context("Fetch response")?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = resp.into_body().collect().await?.to_bytes();
+    assert_eq!(body, "<p>Default</p>");
+
+    let req = Request::builder()
+        .uri("/docs")
+        .body(Empty::<Bytes>::new())?;
+    let resp = srv.clone().call(req).await.context("Fetch response")?;
+    assert_eq!(resp.status(), StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(resp.headers()[LOCATION], "docs/");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_serve_spa_fallback() -> Result<()> {
+    tracing_subscriber::fmt::try_init().unwrap_or_default();
+
+    let assets = OwnedMap::builder()
+        .file("index.html", "<div id=\"app\"></div>")
+        .build()?;
+    let srv = StaticService::new(assets).with_fallback(Fallback::default());
+
+    let req = Request::builder()
+        .uri("/settings")
+        .header(ACCEPT, "text/html")
+        .body(Empty::<Bytes>::new())?;
+    let resp = srv.clone().call(req).await.context("Fetch response")?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()[CACHE_CONTROL], "no-cache");
+    let body = resp.into_body().collect().await?.to_bytes();
+    assert_eq!(body, "<div id=\"app\"></div>");
+
+    let req = Request::builder()
+        .uri("/app.xyz.js")
+        .header(ACCEPT, "text/html")
+        .body(Empty::<Bytes>::new())?;
+    let resp = srv.clone().call(req).await.context("Fetch response")?;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
@@ -1,20 +1,224 @@
-use static_assets_codegen::generate;
+//! Embedded files and any ignore files consulted are tracked as inputs with
+//! `include_bytes!`, so editing them triggers a rebuild. Stable Rust has no
+//! way for a macro to track a directory, so adding or removing assets only
+//! triggers a rebuild with the nightly-only `tracked-path` feature.
+//!
+//! On stable, generate the map from a build script instead, which tells
+//! cargo to re-run it when any asset directory changes, including when
+//! files are added or removed:
+//!
+//! ```no_run
+//! // build.rs
+//! use std::{env, path::PathBuf};
+//!
+//! use static_assets_codegen::Generator;
+//!
+//! let out_dir = PathBuf::from(env::var("OUT_DIR").expect("$OUT_DIR"));
+//! Generator::new("static")
+//!     .target(out_dir.join("assets.rs"))
+//!     .write_for_build_script()
+//!     .expect("generate assets");
+//! ```
+//!
+//! And include the generated code in place of the macro:
+//!
+//! ```ignore
+//! static ASSETS: static_assets::Map = include!(concat!(env!("OUT_DIR"), "/assets.rs"));
+//! ```
+#![cfg_attr(feature = "tracked-path", feature(proc_macro_tracked_path))]
+
+use std::collections::HashSet;
+
+use static_assets_codegen::{Encoding, Fingerprint, Generator, Integrity, Options};
 use syn::parse::{Parse, ParseStream};
-use syn::{parse_macro_input, LitStr};
+use syn::punctuated::Punctuated;
+use syn::{bracketed, parse_macro_input, Ident, LitBool, LitStr, Token};
 
 struct Input {
     path: syn::LitStr,
+    options: Options,
 }
 impl Parse for Input {
     fn parse(input: ParseStream) -> syn::parse::Result<Self> {
         let path: LitStr = input.parse()?;
-        Ok(Input { path })
+        let mut options = Options::default();
+        let mut seen = HashSet::new();
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            if !seen.insert(key.to_string()) {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("duplicate option `{}`", key),
+                ));
+            }
+            match key.to_string().as_str() {
+                "prefix" => options.prefix = input.parse::<LitStr>()?.value(),
+                "include" => options.include = parse_strings(input)?,
+                "exclude" => options.exclude = parse_strings(input)?,
+                "compress" => options.compress = parse_idents(input, parse_encoding)?,
+                "integrity" => options.integrity = parse_idents(input, parse_integrity)?,
+                "fingerprint" => options.fingerprint = parse_fingerprint(input)?,
+                "skip_hidden" => options.skip_hidden = input.parse::<LitBool>()?.value,
+                "passthrough" => options.passthrough = input.parse::<LitBool>()?.value,
+                "respect_ignore_files" => {
+                    options.respect_ignore_files = input.parse::<LitBool>()?.value
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("unknown option `{}`", key),
+                    ))
+                }
+            }
+        }
+
+        Ok(Input { path, options })
+    }
+}
+
+fn parse_strings(input: ParseStream) -> syn::parse::Result<Vec<String>> {
+    let content;
+    bracketed!(content in input);
+    let items = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+    Ok(items.iter().map(LitStr::value).collect())
+}
+
+fn parse_idents<T>(
+    input: ParseStream,
+    parse: fn(&Ident) -> syn::parse::Result<T>,
+) -> syn::parse::Result<Vec<T>> {
+    let content;
+    bracketed!(content in input);
+    let items = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+    items.iter().map(parse).collect()
+}
+
+fn parse_encoding(ident: &Ident) -> syn::parse::Result<Encoding> {
+    match ident.to_string().as_str() {
+        "gzip" => Ok(Encoding::Gzip),
+        "br" => Ok(Encoding::Brotli),
+        "zstd" => Ok(Encoding::Zstd),
+        _ => Err(syn::Error::new(
+            ident.span(),
+            format!(
+                "unknown encoding `{}`, expected one of `gzip`, `br` or `zstd`",
+                ident
+            ),
+        )),
     }
 }
 
+fn parse_integrity(ident: &Ident) -> syn::parse::Result<Integrity> {
+    match ident.to_string().as_str() {
+        "sha256" => Ok(Integrity::Sha256),
+        "sha384" => Ok(Integrity::Sha384),
+        "sha512" => Ok(Integrity::Sha512),
+        _ => Err(syn::Error::new(
+            ident.span(),
+            format!(
+                "unknown algorithm `{}`, expected one of `sha256`, `sha384` or `sha512`",
+                ident
+            ),
+        )),
+    }
+}
+
+// `true` serves fingerprinted names alongside the originals; `instead`
+// only serves fingerprinted names.
+fn parse_fingerprint(input: ParseStream) -> syn::parse::Result<Fingerprint> {
+    if input.peek(LitBool) {
+        return Ok(match input.parse::<LitBool>()?.value {
+            true => Fingerprint::Alongside,
+            false => Fingerprint::Off,
+        });
+    }
+
+    let ident: Ident = input.parse()?;
+    match ident.to_string().as_str() {
+        "off" => Ok(Fingerprint::Off),
+        "alongside" => Ok(Fingerprint::Alongside),
+        "instead" => Ok(Fingerprint::Instead),
+        _ => Err(syn::Error::new(
+            ident.span(),
+            format!(
+                "unknown fingerprint mode `{}`, expected a boolean, `alongside` or `instead`",
+                ident
+            ),
+        )),
+    }
+}
+
+// Includes the underlying causes, as these usually say what actually went
+// wrong, eg: that a directory doesn't exist.
+// Paths within the asset directory are shown as written in the macro call,
+// rather than resolved against the crate root, which varies between
+// machines and build directories.
+fn describe(error: &dyn std::error::Error, dir: &str) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(root) => {
+            let resolved = std::path::Path::new(&root).join(dir);
+            message.replace(&*resolved.to_string_lossy(), dir)
+        }
+        None => message,
+    }
+}
+
+/// Embeds a directory, relative to the crate root, as a `static_assets::Map`.
+///
+/// Options follow the path as `key = value` pairs:
+///
+/// ```ignore
+/// static ASSETS: Map = assets!(
+///     "static",
+///     prefix = "/assets",
+///     include = ["**/*.js", "**/*.css"],
+///     exclude = ["*.map"],
+///     compress = [gzip, br, zstd],
+///     integrity = [sha384],
+///     fingerprint = true, // or `instead`
+///     skip_hidden = true,
+///     respect_ignore_files = true,
+///     passthrough = true, // read from disk in debug builds
+/// );
+/// ```
 #[proc_macro]
 pub fn assets(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let Input { path } = parse_macro_input!(input as Input);
+    let Input { path, options } = parse_macro_input!(input as Input);
+
+    let (tokens, inputs) = match Generator::new(path.value())
+        .options(options)
+        .generate_with_inputs()
+    {
+        Ok(generated) => generated,
+        Err(error) => {
+            return syn::Error::new(path.span(), describe(&error, &path.value()))
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    #[cfg(feature = "tracked-path")]
+    for dir in &inputs.directories {
+        if let Some(dir) = dir.to_str() {
+            proc_macro::tracked::path(dir);
+        }
+    }
+    #[cfg(not(feature = "tracked-path"))]
+    let _ = inputs;
 
-    generate(path.value().as_ref()).expect("generate").into()
+    tokens.into()
 }
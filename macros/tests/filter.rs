@@ -0,0 +1,43 @@
+use std::collections::BTreeSet;
+
+use static_assets::Map;
+use static_assets_macros::assets;
+
+static ALL: Map = assets!("tests/assets");
+static VISIBLE: Map = assets!("tests/assets", skip_hidden = true);
+static WITHOUT_MAPS: Map = assets!("tests/assets", exclude = ["*.map"]);
+static ONLY_JS: Map = assets!("tests/assets", include = ["js/**"], exclude = ["*.map"],);
+
+fn names(map: &'static Map<'static>) -> BTreeSet<&'static str> {
+    map.iter().map(|a| a.name).collect()
+}
+
+#[test]
+fn should_embed_everything_by_default() {
+    let names = names(&ALL);
+
+    assert!(names.contains(".well-known/security.txt"), "{:?}", names);
+    assert!(names.contains("js/canary.js.map"), "{:?}", names);
+}
+
+#[test]
+fn should_skip_hidden_files() {
+    let names = names(&VISIBLE);
+
+    assert!(!names.contains(".well-known/security.txt"), "{:?}", names);
+    assert!(VISIBLE.get(".well-known/security.txt").is_none());
+    assert!(names.contains("canary.html"), "{:?}", names);
+}
+
+#[test]
+fn should_exclude_matching_files() {
+    let names = names(&WITHOUT_MAPS);
+
+    assert!(!names.contains("js/canary.js.map"), "{:?}", names);
+    assert!(names.contains("js/canary.js"), "{:?}", names);
+}
+
+#[test]
+fn should_only_include_matching_files() {
+    assert_eq!(names(&ONLY_JS), BTreeSet::from(["js/canary.js"]));
+}
@@ -0,0 +1,47 @@
+use std::collections::BTreeSet;
+
+use static_assets::Map;
+use static_assets_macros::assets;
+
+static ALL: Map = assets!("tests/ignore-assets", skip_hidden = true);
+static RESPECTING: Map = assets!(
+    "tests/ignore-assets",
+    skip_hidden = true,
+    respect_ignore_files = true
+);
+static SHOWING_HIDDEN: Map = assets!("tests/ignore-assets", respect_ignore_files = true);
+
+fn names(map: &'static Map<'static>) -> BTreeSet<&'static str> {
+    map.iter().map(|a| a.name).collect()
+}
+
+#[test]
+fn should_embed_ignored_files_by_default() {
+    assert_eq!(
+        names(&ALL),
+        BTreeSet::from([
+            "drafts/post.html",
+            "index.html",
+            "nested/page.html",
+            "nested/page.html.bak",
+            "notes.txt",
+            "scratch.tmp",
+        ])
+    );
+}
+
+#[test]
+fn should_skip_files_matched_by_ignore_files() {
+    assert_eq!(
+        names(&RESPECTING),
+        BTreeSet::from(["index.html", "nested/page.html"])
+    );
+}
+
+#[test]
+fn should_not_embed_ignore_files() {
+    assert_eq!(
+        names(&SHOWING_HIDDEN),
+        BTreeSet::from(["index.html", "nested/page.html"])
+    );
+}
@@ -0,0 +1,58 @@
+use static_assets::Map;
+use static_assets_macros::assets;
+
+static PREFIXED: Map = assets!("tests/assets", prefix = "/static/", exclude = ["*.map"]);
+static BROTLI_ONLY: Map = assets!("tests/assets", compress = [br]);
+static UNCOMPRESSED: Map = assets!("tests/assets", compress = []);
+static FINGERPRINTED: Map = assets!("tests/assets", fingerprint = true, integrity = [sha256]);
+static FINGERPRINTED_ONLY: Map = assets!("tests/assets", fingerprint = instead);
+
+#[test]
+fn should_serve_assets_under_prefix() {
+    assert!(PREFIXED.get("static/canary.html").is_some());
+    assert!(PREFIXED.get("canary.html").is_none());
+    assert!(PREFIXED.get("static/js/canary.js.map").is_none());
+}
+
+#[test]
+fn should_only_precompress_with_chosen_encodings() {
+    let asset = BROTLI_ONLY.get("css/canary.css").expect("css/canary.css");
+    assert!(asset.brotli.is_some());
+    assert!(asset.gzip.is_none());
+    assert!(asset.zstd.is_none());
+
+    let asset = UNCOMPRESSED.get("css/canary.css").expect("css/canary.css");
+    assert!(asset.brotli.is_none());
+}
+
+#[test]
+fn should_fingerprint_assets() {
+    let asset = FINGERPRINTED.get("canary.html").expect("canary.html");
+    let fingerprinted = asset.fingerprinted_name.expect("fingerprinted name");
+
+    assert!(FINGERPRINTED.get(fingerprinted).is_some());
+    assert!(asset.integrity().expect("integrity").starts_with("sha256-"));
+
+    assert!(FINGERPRINTED_ONLY.get("canary.html").is_none());
+    assert!(FINGERPRINTED_ONLY.get(fingerprinted).is_some());
+}
+
+#[test]
+fn should_only_pass_through_in_debug_builds() {
+    static LIVE: Map = assets!("tests/assets", passthrough = true);
+
+    assert_eq!(LIVE.source_dir.is_some(), cfg!(debug_assertions));
+    assert!(PREFIXED.source_dir.is_none());
+}
+
+#[test]
+fn should_pass_prefix_through_with_source_dir() {
+    static LIVE_PREFIXED: Map = assets!("tests/assets", prefix = "/static/", passthrough = true);
+
+    if let Some(source_dir) = LIVE_PREFIXED.source_dir {
+        assert_eq!(source_dir.prefix, "static");
+        let path = std::path::Path::new(source_dir.path).join("canary.html");
+        assert!(path.is_file(), "{}", path.display());
+    }
+    assert!(LIVE_PREFIXED.get("static/canary.html").is_some());
+}
@@ -0,0 +1,27 @@
+use static_assets::Map;
+use static_assets_macros::assets;
+
+// Names whose order differs when compared as paths rather than strings.
+static ASSETS: Map = assets!("tests/order-assets");
+static FINGERPRINTED: Map = assets!("tests/order-assets", fingerprint = instead);
+
+const NAMES: [&str; 8] = [
+    "A.css", "a b.css", "a-b.css", "a.css", "a/a.css", "a/b.css", "a0.css", "a_b.css",
+];
+
+#[test]
+fn should_find_every_asset() {
+    assert_eq!(ASSETS.check(), Ok(()));
+    for name in NAMES {
+        assert!(ASSETS.get(name).is_some(), "{}", name);
+    }
+}
+
+#[test]
+fn should_find_every_fingerprinted_asset() {
+    assert_eq!(FINGERPRINTED.check(), Ok(()));
+    for asset in FINGERPRINTED.iter() {
+        let name = asset.fingerprinted_name.expect("fingerprinted name");
+        assert_eq!(FINGERPRINTED.get(name).expect(name).name, asset.name);
+    }
+}
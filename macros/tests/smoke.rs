@@ -1,6 +1,7 @@
 use std::collections::BTreeSet;
+use std::io::Read;
 
-use static_assets::Map;
+use static_assets::{Encoding, Map};
 use static_assets_macros::assets;
 
 static ASSETS: Map = assets!("tests/assets");
@@ -54,3 +55,38 @@ fn should_have_relatively_unique_digests() {
 
     assert_eq!(res.content_type, "text/html");
 }
+
+#[test]
+fn should_store_compressed_variants_that_save_space() {
+    let res = ASSETS.get("css/canary.css").expect("asset css/canary.css");
+
+    let gzip = res.gzip.expect("gzip variant");
+    let mut decoded = Vec::new();
+    flate2::read::GzDecoder::new(gzip)
+        .read_to_end(&mut decoded)
+        .expect("gunzip");
+    assert_eq!(decoded, res.content);
+
+    let brotli = res.brotli.expect("brotli variant");
+    let mut decoded = Vec::new();
+    brotli::Decompressor::new(brotli, 4096)
+        .read_to_end(&mut decoded)
+        .expect("brotli decode");
+    assert_eq!(decoded, res.content);
+
+    let zstd = res.zstd.expect("zstd variant");
+    let decoded = zstd::decode_all(zstd).expect("zstd decode");
+    assert_eq!(decoded, res.content);
+}
+
+#[test]
+fn should_omit_compressed_variants_that_do_not_save_space() {
+    let res = ASSETS.get("canary.html").expect("asset canary.html");
+
+    assert_eq!(
+        res.encodings().collect::<Vec<_>>(),
+        vec![Encoding::Identity],
+        "Encodings for {:?}",
+        res
+    );
+}
@@ -0,0 +1,6 @@
+use static_assets::Map;
+use static_assets_macros::assets;
+
+static ASSETS: Map = assets!("tests/assets", compress = [gzip, deflate]);
+
+fn main() {}